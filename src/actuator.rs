@@ -0,0 +1,246 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{ball::Ball, config::BoardConfig, GameState};
+
+pub struct ActuatorPlugin;
+
+impl Plugin for ActuatorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SetActuator>().add_systems(
+            Update,
+            (switch_actuators, push_gates, move_actuators)
+                .chain()
+                .run_if(in_state(GameState::Loaded)),
+        );
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ActuatorKind {
+    /// a one-way flap hinged at its position : a ball coming from the side it opens away from
+    /// pushes it open, the other side is blocked until the rules open it
+    Gate,
+    /// a wall hinged at its position, redirecting a lane
+    Diverter,
+    /// a post centered at its position, rising from the board
+    Post,
+}
+
+/// Position of an actuator, relative to its configured position
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Pose {
+    pub offset: Vec3,
+    /// rotation around the board normal
+    pub angle: f32,
+}
+
+impl Pose {
+    fn lerp(&self, other: &Pose, t: f32) -> Pose {
+        Pose {
+            offset: self.offset.lerp(other.offset, t),
+            angle: self.angle + (other.angle - self.angle) * t,
+        }
+    }
+}
+
+/// A moving element controlled by the rules, going between its closed and open poses
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ActuatorConfig {
+    pub id: String,
+    pub kind: ActuatorKind,
+    /// position on the board
+    pub pos: Vec3,
+    pub size: Vec3,
+    #[serde(default)]
+    pub closed: Pose,
+    pub open: Pose,
+    /// open when the level starts
+    #[serde(default)]
+    pub opened: bool,
+    /// duration of the move between the poses, in seconds
+    #[serde(default = "ActuatorConfig::default_move_time")]
+    pub move_time: f32,
+}
+
+impl ActuatorConfig {
+    fn default_move_time() -> f32 {
+        0.2
+    }
+
+    /// Distance from its position the actuator can reach on the board, whatever its angle
+    fn reach(&self) -> f32 {
+        match self.kind {
+            ActuatorKind::Post => Vec2::new(self.size.x, self.size.z).length() / 2.0,
+            ActuatorKind::Gate | ActuatorKind::Diverter => self.size.x,
+        }
+    }
+
+    /// Check the size of the actuator and that it stays on the board, open or closed
+    pub fn validate(&self, board: &BoardConfig) -> Result<(), String> {
+        let size = self.size;
+        if size.x <= 0.0 || size.y <= 0.0 || size.z <= 0.0 {
+            return Err(format!("size {size} should be positive"));
+        }
+        let reach = self.reach();
+        for pose in [&self.closed, &self.open] {
+            if !board.contains(self.pos + pose.offset, reach, reach) {
+                return Err(format!("actuator at {} is out of the board", self.pos));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Open or close an actuator
+#[derive(Event)]
+pub struct SetActuator {
+    pub id: String,
+    pub open: bool,
+}
+
+#[derive(Component)]
+pub struct Actuator {
+    pub id: String,
+    kind: ActuatorKind,
+    /// position of the center of the base, relative to the board
+    base: Vec3,
+    size: Vec3,
+    closed: Pose,
+    open: Pose,
+    move_time: f32,
+    opened: bool,
+    /// a gate pushed open by a ball
+    pushed: bool,
+    /// 0 when closed, 1 when open
+    progress: f32,
+}
+
+impl Actuator {
+    const COLOR: Color = Color::GOLD;
+
+    fn transform(&self) -> Transform {
+        let pose = self.closed.lerp(&self.open, self.progress);
+        let rotation = Quat::from_rotation_y(pose.angle);
+        let pos = self.base + pose.offset;
+        let translation = match self.kind {
+            ActuatorKind::Post => pos,
+            // the hinge is at one end
+            ActuatorKind::Gate | ActuatorKind::Diverter => {
+                pos + rotation * Vec3::new(self.size.x / 2.0, 0.0, 0.0)
+            }
+        };
+        Transform::from_translation(translation).with_rotation(rotation)
+    }
+
+    /// Sensor detecting the balls coming to a gate from the side it opens away from, in front
+    /// of the closed gate, as deep as the gate is long
+    fn gate_sensor(&self) -> (Transform, Collider) {
+        let rotation = Quat::from_rotation_y(self.closed.angle);
+        let side = (self.open.angle - self.closed.angle).signum();
+        let depth = self.size.x;
+        let center = Vec3::new(self.size.x / 2.0, 0.0, side * (depth + self.size.z) / 2.0);
+        let transform =
+            Transform::from_translation(self.base + self.closed.offset + rotation * center)
+                .with_rotation(rotation);
+        let collider = Collider::cuboid(self.size.x / 2.0, self.size.y / 2.0, depth / 2.0);
+        (transform, collider)
+    }
+}
+
+/// Opens its gate when a ball is inside
+#[derive(Component)]
+struct GateSensor {
+    gate: Entity,
+}
+
+pub fn spawn_actuator(
+    builder: &mut ChildBuilder,
+    config: &ActuatorConfig,
+    board: &BoardConfig,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+) {
+    let size = config.size;
+    let actuator = Actuator {
+        id: config.id.clone(),
+        kind: config.kind,
+        base: Vec3::new(config.pos.x, board.surface() + size.y / 2.0, config.pos.z),
+        size,
+        closed: config.closed,
+        open: config.open,
+        move_time: config.move_time,
+        opened: config.opened,
+        pushed: false,
+        progress: if config.opened { 1.0 } else { 0.0 },
+    };
+    let sensor = (actuator.kind == ActuatorKind::Gate).then(|| actuator.gate_sensor());
+    let gate = builder.spawn((
+        Name::new(format!("ACTUATOR {}", config.id)),
+        PbrBundle {
+            mesh: meshes.add(shape::Box::new(size.x, size.y, size.z).into()),
+            transform: actuator.transform(),
+            material: materials.add(Actuator::COLOR.into()),
+            ..default()
+        },
+        actuator,
+        RigidBody::KinematicPositionBased,
+        Collider::cuboid(size.x / 2.0, size.y / 2.0, size.z / 2.0),
+    ));
+    if let Some((transform, collider)) = sensor {
+        let gate = gate.id();
+        builder.spawn((
+            Name::new(format!("GATE SENSOR {}", config.id)),
+            GateSensor { gate },
+            TransformBundle::from_transform(transform),
+            collider,
+            Sensor,
+        ));
+    }
+}
+
+fn switch_actuators(mut requests: EventReader<SetActuator>, mut q_actuators: Query<&mut Actuator>) {
+    for request in requests.read() {
+        for mut actuator in q_actuators.iter_mut().filter(|a| a.id == request.id) {
+            actuator.opened = request.open;
+        }
+    }
+}
+
+fn push_gates(
+    q_sensors: Query<(Entity, &GateSensor)>,
+    q_balls: Query<Entity, With<Ball>>,
+    mut q_actuators: Query<&mut Actuator>,
+    rapier_context: Res<RapierContext>,
+) {
+    for (sensor_entity, sensor) in q_sensors.iter() {
+        let pushed = q_balls
+            .iter()
+            .any(|ball| rapier_context.intersection_pair(sensor_entity, ball) == Some(true));
+        if let Ok(mut gate) = q_actuators.get_mut(sensor.gate) {
+            if gate.pushed != pushed {
+                gate.pushed = pushed;
+            }
+        }
+    }
+}
+
+/// Move the actuators toward their target pose
+fn move_actuators(mut q_actuators: Query<(&mut Actuator, &mut Transform)>, time: Res<Time>) {
+    for (mut actuator, mut transform) in q_actuators.iter_mut() {
+        let target = if actuator.opened || actuator.pushed {
+            1.0
+        } else {
+            0.0
+        };
+        if actuator.progress == target {
+            continue;
+        }
+        let step = time.delta_seconds() / actuator.move_time.max(f32::EPSILON);
+        actuator.progress += (target - actuator.progress).clamp(-step, step);
+        *transform = actuator.transform();
+    }
+}
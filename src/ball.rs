@@ -1,65 +1,276 @@
+use std::collections::HashMap;
+
 use bevy::{
     prelude::*,
     render::render_resource::{Extent3d, TextureDimension, TextureFormat},
 };
 use bevy_rapier3d::prelude::*;
 
-use crate::config::PinballConfig;
+use crate::{config::PinballConfig, plunger::BallLaunched, GameState};
 
 pub struct BallPlugin;
 
 impl Plugin for BallPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, spawn_ball);
+        app.add_event::<BallStateChanged>()
+            .add_event::<SpawnBall>()
+            .init_resource::<BallPool>()
+            .add_systems(Startup, init_ball_assets)
+            .add_systems(OnEnter(GameState::Loading), despawn_balls)
+            .add_systems(
+                Update,
+                (spawn_balls, put_ball_in_play, drain_ball).run_if(in_state(GameState::Loaded)),
+            );
     }
 }
 
-#[derive(Component)]
-pub struct Ball;
+/// Lifecycle of a ball
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BallState {
+    /// waiting in the plunger lane
+    #[default]
+    InLane,
+    InPlay,
+    /// in the drain, it will be drained after [Ball::DRAIN_DELAY]
+    Draining,
+    /// out of the game, it is despawned
+    Drained,
+}
 
-fn spawn_ball(
+/// Sent each time a ball changes of [BallState]
+#[derive(Event)]
+pub struct BallStateChanged {
+    pub ball: Entity,
+    pub state: BallState,
+}
+
+/// Where a new ball is spawned
+#[derive(Clone, Copy, Debug)]
+pub enum BallStart {
+    /// at the start position, waiting for the plunger if any
+    Lane,
+    /// at the start position, launched by the plunger at full strength
+    Launched,
+}
+
+/// Ask for a new ball on the table
+#[derive(Event)]
+pub struct SpawnBall(pub BallStart);
+
+/// The balls on the table, by id
+#[derive(Default, Resource)]
+pub struct BallPool {
+    next_id: u32,
+    balls: HashMap<u32, Entity>,
+}
+
+impl BallPool {
+    /// Number of balls on the table, including the draining ones
+    pub fn len(&self) -> usize {
+        self.balls.len()
+    }
+
+    fn add(&mut self, entity: Entity) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.balls.insert(id, entity);
+        id
+    }
+}
+
+#[derive(Component, Default)]
+pub struct Ball {
+    /// id of the ball in the [BallPool]
+    id: u32,
+    state: BallState,
+    /// time since the last state change, in seconds
+    state_time: f32,
+}
+
+impl Ball {
+    pub const RADIUS: f32 = 1.0;
+    /// time spent in the drain before the ball is drained, in seconds
+    const DRAIN_DELAY: f32 = 1.0;
+    /// a ball falling below this height is drained, even if it missed the drain
+    const FALL_LIMIT: f32 = -20.0;
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn state(&self) -> BallState {
+        self.state
+    }
+
+    /// Change the state of the ball, returning the event to send
+    pub fn set_state(&mut self, ball: Entity, state: BallState) -> BallStateChanged {
+        self.state = state;
+        self.state_time = 0.0;
+        BallStateChanged { ball, state }
+    }
+}
+
+#[derive(Resource)]
+pub struct BallAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+}
+
+/// Order the entities of a collision as `(ball, other)`, if only one of them is a ball
+pub fn ball_and_other(
+    e1: Entity,
+    e2: Entity,
+    q_ball: &Query<(), With<Ball>>,
+) -> Option<(Entity, Entity)> {
+    match (q_ball.contains(e1), q_ball.contains(e2)) {
+        (true, false) => Some((e1, e2)),
+        (false, true) => Some((e2, e1)),
+        _ => None,
+    }
+}
+
+/// The `(ball, other)` entities of a collision starting between a ball and another element
+pub fn ball_collision_started(
+    event: &CollisionEvent,
+    q_ball: &Query<(), With<Ball>>,
+) -> Option<(Entity, Entity)> {
+    match *event {
+        CollisionEvent::Started(e1, e2, _) => ball_and_other(e1, e2, q_ball),
+        CollisionEvent::Stopped(..) => None,
+    }
+}
+
+fn init_ball_assets(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut images: ResMut<Assets<Image>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
-    pinball_config: Res<PinballConfig>,
 ) {
     let debug_material = materials.add(StandardMaterial {
         base_color_texture: Some(images.add(uv_debug_texture())),
         ..default()
     });
 
-    const BALL_RADIUS: f32 = 1.0;
+    commands.insert_resource(BallAssets {
+        mesh: meshes.add(
+            shape::UVSphere {
+                radius: Ball::RADIUS,
+                ..default()
+            }
+            .into(),
+        ),
+        material: debug_material,
+    });
+}
 
-    commands
+/// Spawn a new ball, with its id in the pool
+fn spawn_ball(
+    commands: &mut Commands,
+    assets: &BallAssets,
+    pool: &mut BallPool,
+    pinball_config: &PinballConfig,
+    start: BallStart,
+) -> Entity {
+    let (impulse, state) = match (start, &pinball_config.plunger) {
+        (BallStart::Lane, Some(_)) => (Vec3::ZERO, BallState::InLane),
+        (BallStart::Launched, Some(plunger)) => {
+            let direction = Quat::from_rotation_x(pinball_config.board.angle)
+                * plunger.direction.normalize_or_zero();
+            (direction * plunger.max_impulse, BallState::InPlay)
+        }
+        (_, None) => (Vec3::ZERO, BallState::InPlay),
+    };
+    let entity = commands
         .spawn((
             Name::new("Ball"),
-            Ball,
             PbrBundle {
-                mesh: meshes.add(
-                    shape::UVSphere {
-                        radius: BALL_RADIUS,
-                        ..default()
-                    }
-                    .into(),
-                ),
-                material: debug_material,
-                transform: Transform::from_translation(pinball_config.board.ball),
+                mesh: assets.mesh.clone(),
+                material: assets.material.clone(),
+                transform: Transform::from_translation(pinball_config.ball_start()),
                 ..default()
             },
         ))
         .insert((
             RigidBody::Dynamic,
-            Collider::ball(BALL_RADIUS),
+            Collider::ball(Ball::RADIUS),
             ColliderMassProperties::Mass(20.0),
-        ));
+            ActiveEvents::COLLISION_EVENTS,
+            ExternalImpulse {
+                impulse,
+                ..default()
+            },
+            ExternalForce::default(),
+            Velocity::default(),
+        ))
+        .id();
+    let id = pool.add(entity);
+    commands.entity(entity).insert(Ball {
+        id,
+        state,
+        ..default()
+    });
+    entity
 }
 
-// fn despawn_ball(mut commands: Commands, player_query: Query<Entity, With<Ball>>) {
-//     if let Ok(player_entity) = player_query.get_single() {
-//         commands.entity(player_entity).despawn();
-//     }
-// }
+fn spawn_balls(
+    mut commands: Commands,
+    mut requests: EventReader<SpawnBall>,
+    assets: Res<BallAssets>,
+    mut pool: ResMut<BallPool>,
+    pinball_config: Res<PinballConfig>,
+) {
+    for SpawnBall(start) in requests.read() {
+        spawn_ball(&mut commands, &assets, &mut pool, &pinball_config, *start);
+    }
+}
+
+fn despawn_balls(
+    mut commands: Commands,
+    q_ball: Query<Entity, With<Ball>>,
+    mut pool: ResMut<BallPool>,
+) {
+    for entity in q_ball.iter() {
+        commands.entity(entity).despawn();
+    }
+    *pool = BallPool::default();
+}
+
+fn put_ball_in_play(
+    mut launched: EventReader<BallLaunched>,
+    mut q_balls: Query<&mut Ball>,
+    mut state_changed: EventWriter<BallStateChanged>,
+) {
+    for event in launched.read() {
+        if let Ok(mut ball) = q_balls.get_mut(event.ball) {
+            if ball.state == BallState::InLane {
+                state_changed.send(ball.set_state(event.ball, BallState::InPlay));
+            }
+        }
+    }
+}
+
+/// Drain the balls that stayed long enough in the drain, or fell from the board
+fn drain_ball(
+    mut commands: Commands,
+    mut q_balls: Query<(Entity, &mut Ball, &Transform)>,
+    mut pool: ResMut<BallPool>,
+    time: Res<Time>,
+    mut state_changed: EventWriter<BallStateChanged>,
+) {
+    for (entity, mut ball, transform) in q_balls.iter_mut() {
+        ball.state_time += time.delta_seconds();
+        let drained = match ball.state {
+            BallState::Drained => false,
+            BallState::Draining => ball.state_time >= Ball::DRAIN_DELAY,
+            _ => transform.translation.y < Ball::FALL_LIMIT,
+        };
+        if drained {
+            state_changed.send(ball.set_state(entity, BallState::Drained));
+            pool.balls.remove(&ball.id);
+            commands.entity(entity).despawn();
+        }
+    }
+}
 
 /// Creates a colorful test pattern
 fn uv_debug_texture() -> Image {
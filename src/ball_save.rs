@@ -0,0 +1,182 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{config::PinballConfig, plunger::BallLaunched, GameState};
+
+pub struct BallSavePlugin;
+
+impl Plugin for BallSavePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BallSave>()
+            .add_event::<StartBallSave>()
+            .add_event::<BallSaved>()
+            .add_systems(OnEnter(GameState::Loaded), reset_ball_save)
+            .add_systems(Update, update_ball_save.run_if(in_state(GameState::Loaded)));
+    }
+}
+
+/// Ball save of the level, starting when the ball is launched
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct BallSaveConfig {
+    /// time after the launch during which a drained ball is returned, in seconds
+    pub window: f32,
+}
+
+/// Start a ball save, for a given time in seconds
+#[derive(Event)]
+pub struct StartBallSave {
+    pub time: f32,
+}
+
+/// Sent when a drained ball is returned to the plunger
+#[derive(Event)]
+pub struct BallSaved {
+    pub ball: Entity,
+}
+
+#[derive(Default, Resource)]
+pub struct BallSave {
+    /// time left, in seconds
+    timer: f32,
+    /// the window starts on the next launch : only the first launch of each ball starts it
+    armed: bool,
+    /// balls which entered the drain during the ball save, saved once drained
+    latched: Vec<Entity>,
+}
+
+impl BallSave {
+    pub fn is_active(&self) -> bool {
+        self.timer > 0.0
+    }
+
+    pub fn cancel(&mut self) {
+        self.timer = 0.0;
+    }
+
+    /// Start the window on the next launch, for a new ball
+    pub fn arm(&mut self) {
+        self.armed = true;
+    }
+
+    /// A ball enters the drain : it will be saved if the ball save is active now, even if
+    /// it is over when the ball is drained
+    pub fn latch(&mut self, ball: Entity) {
+        if self.is_active() {
+            self.latched.push(ball);
+        }
+    }
+
+    /// Check if a drained ball is saved : latched in the drain, or falling from the board
+    /// during the ball save
+    pub fn saves(&mut self, ball: Entity) -> bool {
+        match self.latched.iter().position(|&latched| latched == ball) {
+            Some(index) => {
+                self.latched.swap_remove(index);
+                true
+            }
+            None => self.is_active(),
+        }
+    }
+
+    /// Start the window if it is the first launch of the ball
+    fn launch(&mut self, window: f32) {
+        if self.armed {
+            self.armed = false;
+            self.timer = self.timer.max(window);
+        }
+    }
+
+    fn tick(&mut self, delta: f32) {
+        self.timer = (self.timer - delta).max(0.0);
+    }
+}
+
+fn reset_ball_save(mut ball_save: ResMut<BallSave>) {
+    *ball_save = BallSave {
+        armed: true,
+        ..default()
+    };
+}
+
+/// Start the ball save on the first launch of a ball or when asked to, and count down its time
+fn update_ball_save(
+    mut ball_save: ResMut<BallSave>,
+    mut launched: EventReader<BallLaunched>,
+    mut requests: EventReader<StartBallSave>,
+    pinball_config: Res<PinballConfig>,
+    time: Res<Time>,
+) {
+    if ball_save.is_active() {
+        ball_save.tick(time.delta_seconds());
+    }
+    if let Some(config) = &pinball_config.ball_save {
+        if launched.read().count() > 0 {
+            ball_save.launch(config.window);
+        }
+    }
+    for request in requests.read() {
+        ball_save.timer = ball_save.timer.max(request.time);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn armed() -> BallSave {
+        BallSave {
+            armed: true,
+            ..default()
+        }
+    }
+
+    #[test]
+    fn window_starts_on_the_first_launch() {
+        let mut ball_save = armed();
+        assert!(!ball_save.is_active());
+        ball_save.launch(5.0);
+        ball_save.tick(4.0);
+        assert!(ball_save.is_active());
+        // launching the same ball again doesn't restart it
+        ball_save.launch(5.0);
+        ball_save.tick(1.0);
+        assert!(!ball_save.is_active());
+        // until the next ball
+        ball_save.arm();
+        ball_save.launch(5.0);
+        assert!(ball_save.is_active());
+    }
+
+    #[test]
+    fn ball_in_the_drain_is_latched() {
+        let mut ball_save = armed();
+        let ball = Entity::from_raw(1);
+        ball_save.launch(5.0);
+        ball_save.latch(ball);
+        // the window is over before the ball is drained
+        ball_save.tick(5.0);
+        assert!(ball_save.saves(ball));
+        // saved once
+        assert!(!ball_save.saves(ball));
+    }
+
+    #[test]
+    fn ball_in_the_drain_after_the_window_is_lost() {
+        let mut ball_save = armed();
+        let ball = Entity::from_raw(1);
+        ball_save.launch(5.0);
+        ball_save.tick(5.0);
+        ball_save.latch(ball);
+        assert!(!ball_save.saves(ball));
+    }
+
+    #[test]
+    fn ball_falling_from_the_board_is_saved_during_the_window() {
+        let mut ball_save = armed();
+        let ball = Entity::from_raw(1);
+        ball_save.launch(5.0);
+        assert!(ball_save.saves(ball));
+        ball_save.cancel();
+        assert!(!ball_save.saves(ball));
+    }
+}
@@ -1,24 +1,44 @@
-use bevy::prelude::*;
+use bevy::{asset::LoadState, prelude::*};
 use bevy_rapier3d::prelude::*;
 
 use crate::{
-    config::{self, PinballConfig},
+    actuator::spawn_actuator,
+    bumper::spawn_bumper,
+    colliders::{get_scene_colliders, spawn_scene_colliders},
+    config::PinballConfig,
+    drain::spawn_drain,
+    error::GameError,
+    lamp::spawn_lamp,
+    magnet::spawn_magnet,
     paddle::spawn_paddle,
-    GameAssets, GameState,
+    plunger::spawn_plunger,
+    ramp::spawn_ramp,
+    saucer::spawn_saucer,
+    slingshot::spawn_slingshot,
+    spinner::spawn_spinner,
+    switch::spawn_switch,
+    target::spawn_target_bank,
+    Error, GameAssets, GameState,
 };
 
+const SCENE_PATH: &str = "pinball.glb#Scene0";
+
 pub struct BoardPlugin;
 
 impl Plugin for BoardPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(config::load_config())
-            .add_systems(OnEnter(GameState::Loading), (load_scene,))
+        app.add_systems(OnEnter(GameState::Loading), (load_scene, despawn_board))
             .add_systems(
                 Update,
-                (check_if_loaded,).run_if(in_state(GameState::Loading)),
+                (check_if_loaded,)
+                    .run_if(in_state(GameState::Loading))
+                    .run_if(resource_exists::<PinballConfig>()),
             )
-            // .add_systems(OnEnter(GameState::Loaded), (spawn_gltf,))
-            .add_systems(Startup, spawn_board);
+            .add_systems(
+                OnEnter(GameState::Loaded),
+                // the board must exist before the scene is added to it
+                (spawn_board, apply_deferred, spawn_scene).chain(),
+            );
     }
 }
 
@@ -27,34 +47,36 @@ pub struct Board;
 
 impl Board {
     const COLOR: Color = Color::PURPLE;
-    const SIZE: Vec3 = Vec3 {
-        x: 40.0,
-        y: 0.2,
-        z: 40.0,
-    };
-
-    fn hx() -> f32 {
-        Self::SIZE.x / 2.0
-    }
+    const WALL_COLOR: Color = Color::GRAY;
+    /// height of the walls above the board surface
+    const WALL_HEIGHT: f32 = 2.0;
+    const WALL_THICKNESS: f32 = 0.5;
 
-    fn hy() -> f32 {
-        Self::SIZE.y / 2.0
+    fn shape(size: Vec3) -> impl Into<Mesh> {
+        shape::Box::new(size.x, size.y, size.z)
     }
 
-    fn hz() -> f32 {
-        Self::SIZE.z / 2.0
+    fn collider(size: Vec3) -> Collider {
+        Collider::cuboid(size.x / 2.0, size.y / 2.0, size.z / 2.0)
     }
 
-    fn transform() -> Transform {
-        Transform::from_xyz(0.0, -Self::hy(), 0.0)
-    }
-
-    fn shape() -> impl Into<Mesh> {
-        shape::Box::new(Self::SIZE.x, Self::SIZE.y, Self::SIZE.z)
-    }
-
-    fn collider() -> Collider {
-        Collider::cuboid(Self::hx(), Self::hy(), Self::hz())
+    /// Size and position, relative to the board, of the left, right and top walls.
+    /// The bottom of the board is left open.
+    fn walls(size: Vec3) -> [(Vec3, Vec3); 3] {
+        let (hx, hz) = (size.x / 2.0, size.z / 2.0);
+        let y = size.y / 2.0 + Self::WALL_HEIGHT / 2.0;
+        let offset = Self::WALL_THICKNESS / 2.0;
+        let side = Vec3::new(Self::WALL_THICKNESS, Self::WALL_HEIGHT, size.z);
+        let top = Vec3::new(
+            size.x + 2.0 * Self::WALL_THICKNESS,
+            Self::WALL_HEIGHT,
+            Self::WALL_THICKNESS,
+        );
+        [
+            (side, Vec3::new(-hx - offset, y, 0.0)),
+            (side, Vec3::new(hx + offset, y, 0.0)),
+            (top, Vec3::new(0.0, y, -hz - offset)),
+        ]
     }
 }
 
@@ -65,43 +87,139 @@ fn spawn_board(
     mut materials: ResMut<Assets<StandardMaterial>>,
     pinball_config: Res<PinballConfig>,
 ) {
+    let board_config = &pinball_config.board;
+    let wall_material = materials.add(Board::WALL_COLOR.into());
     commands
         .spawn((
             Name::new("BOARD"),
             Board,
             PbrBundle {
-                mesh: meshes.add(Board::shape().into()),
-                transform: Board::transform()
-                    .with_rotation(Quat::from_rotation_x(pinball_config.board.angle)),
+                mesh: meshes.add(Board::shape(board_config.size).into()),
+                transform: board_config.transform(),
                 material: materials.add(Board::COLOR.into()),
                 ..default()
             },
         ))
-        .insert((RigidBody::Fixed, Board::collider()))
+        .insert((RigidBody::Fixed, Board::collider(board_config.size)))
         .with_children(|builder| {
+            for (size, pos) in Board::walls(board_config.size) {
+                builder.spawn((
+                    Name::new("WALL"),
+                    PbrBundle {
+                        mesh: meshes.add(shape::Box::new(size.x, size.y, size.z).into()),
+                        transform: Transform::from_translation(pos),
+                        material: wall_material.clone(),
+                        ..default()
+                    },
+                    Collider::cuboid(size.x / 2.0, size.y / 2.0, size.z / 2.0),
+                ));
+            }
             for pcfg in pinball_config.paddles.iter() {
-                spawn_paddle(builder, pcfg, &mut meshes, &mut materials);
+                spawn_paddle(builder, pcfg, board_config, &mut meshes, &mut materials);
+            }
+            for slingshot in pinball_config.slingshots.iter() {
+                spawn_slingshot(
+                    builder,
+                    slingshot,
+                    board_config,
+                    &mut meshes,
+                    &mut materials,
+                );
+            }
+            for bank in pinball_config.targets.iter() {
+                spawn_target_bank(builder, bank, board_config, &mut meshes, &mut materials);
+            }
+            for switch in pinball_config.switches.iter() {
+                spawn_switch(builder, switch, board_config, &mut meshes, &mut materials);
+            }
+            for spinner in pinball_config.spinners.iter() {
+                spawn_spinner(builder, spinner, board_config, &mut meshes, &mut materials);
+            }
+            for ramp in pinball_config.ramps.iter() {
+                spawn_ramp(builder, ramp, board_config, &mut meshes, &mut materials);
+            }
+            for saucer in pinball_config.saucers.iter() {
+                spawn_saucer(builder, saucer, board_config, &mut meshes, &mut materials);
+            }
+            for magnet in pinball_config.magnets.iter() {
+                spawn_magnet(builder, magnet, board_config);
+            }
+            for actuator in pinball_config.actuators.iter() {
+                spawn_actuator(builder, actuator, board_config, &mut meshes, &mut materials);
+            }
+            for lamp in pinball_config.lamps.iter() {
+                spawn_lamp(builder, lamp, board_config, &mut meshes, &mut materials);
+            }
+            if let Some(plunger) = &pinball_config.plunger {
+                spawn_plunger(builder, plunger, board_config, &mut meshes, &mut materials);
+            }
+            if let Some(drain) = &pinball_config.drain {
+                spawn_drain(builder, drain, board_config);
+            }
+            for bumper in pinball_config.bumpers.iter() {
+                spawn_bumper(builder, bumper, board_config, &mut meshes, &mut materials);
             }
         });
 }
 
 // load the scene from the gltf file
 fn load_scene(asset_server: Res<AssetServer>, mut game_assets: ResMut<GameAssets>) {
-    game_assets.scene = asset_server.load("pinball.glb#Scene0");
+    game_assets.scene = asset_server.load(SCENE_PATH);
 }
 
 // check if the scene is loaded and if so, get the colliders from it
 fn check_if_loaded(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
     mut scenes: ResMut<Assets<Scene>>,
     mut game_assets: ResMut<GameAssets>,
-    mut meshes: ResMut<Assets<Mesh>>,
+    meshes: Res<Assets<Mesh>>,
     mut game_state: ResMut<NextState<GameState>>,
 ) {
-    if let Some(scene) = scenes.get_mut(&game_assets.scene) {
+    if asset_server.get_load_state(&game_assets.scene) == Some(LoadState::Failed) {
+        commands.insert_resource(GameError(Error::LoadGltf(SCENE_PATH.into())));
+    } else if game_assets.colliders.is_some() {
+        // the level has been reloaded, the colliders are still valid
+        game_state.set(GameState::Loaded);
+    } else if let Some(scene) = scenes.get_mut(&game_assets.scene) {
         // get_scene_colliders should be called only once per scene as it will remove the colliders meshes from it
-        // game_assets.colliders =
-        //     get_scene_colliders(&mut meshes, &mut scene.world).expect("Failed to create colliders");
+        match get_scene_colliders(&meshes, &mut scene.world) {
+            Ok(colliders) => {
+                game_assets.colliders = Some(colliders);
+                game_state.set(GameState::Loaded);
+            }
+            Err(e) => commands.insert_resource(GameError(e)),
+        }
+    }
+}
 
-        game_state.set(GameState::Loaded);
+// spawn the scene and its colliders on the board
+fn spawn_scene(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    pinball_config: Res<PinballConfig>,
+    q_board: Query<Entity, With<Board>>,
+) {
+    let Ok(board) = q_board.get_single() else {
+        return;
+    };
+    commands.entity(board).with_children(|builder| {
+        builder.spawn((
+            Name::new("SCENE"),
+            SceneBundle {
+                scene: game_assets.scene.clone(),
+                ..default()
+            },
+        ));
+        if let Some(colliders) = &game_assets.colliders {
+            spawn_scene_colliders(builder, colliders, &pinball_config.scores);
+        }
+    });
+}
+
+// despawn the board with its paddles, scene and colliders
+fn despawn_board(mut commands: Commands, q_board: Query<Entity, With<Board>>) {
+    for entity in q_board.iter() {
+        commands.entity(entity).despawn_recursive();
     }
 }
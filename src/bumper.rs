@@ -0,0 +1,167 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ball::{ball_collision_started, Ball},
+    config::BoardConfig,
+    score::Scoring,
+    GameState,
+};
+
+pub struct BumperPlugin;
+
+impl Plugin for BumperPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<BumperHit>().add_systems(
+            Update,
+            (kick_ball, flash_bumpers)
+                .chain()
+                .run_if(in_state(GameState::Loaded)),
+        );
+    }
+}
+
+/// A pop bumper, kicking the ball away when hit
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct BumperConfig {
+    /// position on the board
+    pub pos: Vec3,
+    pub radius: f32,
+    /// impulse applied to the ball when it hits the bumper
+    pub impulse: f32,
+    /// points given when hit
+    #[serde(default)]
+    pub score: u32,
+}
+
+impl BumperConfig {
+    fn transform(&self, board: &BoardConfig) -> Transform {
+        Transform::from_xyz(
+            self.pos.x,
+            board.surface() + Bumper::HEIGHT / 2.0,
+            self.pos.z,
+        )
+    }
+
+    /// Check the radius of the bumper and that it is on the board, returning the reason if not
+    pub fn validate(&self, board: &BoardConfig) -> Result<(), String> {
+        if self.radius <= 0.0 {
+            return Err("radius should be positive".into());
+        }
+        if !board.contains(self.pos, self.radius, self.radius) {
+            return Err(format!("bumper at {} is out of the board", self.pos));
+        }
+        Ok(())
+    }
+}
+
+/// Sent when the ball hits a bumper
+#[derive(Event)]
+pub struct BumperHit {
+    pub bumper: Entity,
+    pub ball: Entity,
+}
+
+#[derive(Component)]
+pub struct Bumper {
+    impulse: f32,
+    /// time left for the flash, in seconds
+    flash: f32,
+}
+
+impl Bumper {
+    /// impulse of the bumpers of the glTF scene, when not given by their extras
+    pub const DEFAULT_IMPULSE: f32 = 300.0;
+    const HEIGHT: f32 = 1.5;
+    const COLOR: Color = Color::RED;
+    const FLASH_COLOR: Color = Color::YELLOW;
+    /// duration of the flash, in seconds
+    const FLASH_TIME: f32 = 0.15;
+
+    pub fn new(impulse: f32) -> Self {
+        Bumper {
+            impulse,
+            flash: 0.0,
+        }
+    }
+}
+
+pub fn spawn_bumper(
+    builder: &mut ChildBuilder,
+    config: &BumperConfig,
+    board: &BoardConfig,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+) {
+    builder.spawn((
+        Name::new("BUMPER"),
+        Bumper::new(config.impulse),
+        PbrBundle {
+            mesh: meshes.add(
+                shape::Cylinder {
+                    radius: config.radius,
+                    height: Bumper::HEIGHT,
+                    ..default()
+                }
+                .into(),
+            ),
+            transform: config.transform(board),
+            // each bumper has its own material, to flash alone
+            material: materials.add(Bumper::COLOR.into()),
+            ..default()
+        },
+        Collider::cylinder(Bumper::HEIGHT / 2.0, config.radius),
+        Scoring(config.score),
+    ));
+}
+
+/// Push the ball away from the center of the bumper it hits, along the board surface
+fn kick_ball(
+    mut collision_events: EventReader<CollisionEvent>,
+    mut q_bumpers: Query<(&mut Bumper, &GlobalTransform)>,
+    q_ball: Query<(), With<Ball>>,
+    mut q_balls: Query<(&GlobalTransform, &mut ExternalImpulse), With<Ball>>,
+    mut hits: EventWriter<BumperHit>,
+) {
+    for event in collision_events.read() {
+        let Some((ball_entity, bumper_entity)) = ball_collision_started(event, &q_ball) else {
+            continue;
+        };
+        let Ok((ball_transform, mut impulse)) = q_balls.get_mut(ball_entity) else {
+            continue;
+        };
+        let Ok((mut bumper, bumper_transform)) = q_bumpers.get_mut(bumper_entity) else {
+            continue;
+        };
+        let normal = bumper_transform.up();
+        let offset = ball_transform.translation() - bumper_transform.translation();
+        let direction = (offset - normal * offset.dot(normal)).normalize_or_zero();
+        impulse.impulse += direction * bumper.impulse;
+        bumper.flash = Bumper::FLASH_TIME;
+        hits.send(BumperHit {
+            bumper: bumper_entity,
+            ball: ball_entity,
+        });
+    }
+}
+
+fn flash_bumpers(
+    mut q_bumpers: Query<(&mut Bumper, &Handle<StandardMaterial>)>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    time: Res<Time>,
+) {
+    for (mut bumper, material) in q_bumpers.iter_mut() {
+        if bumper.flash <= 0.0 {
+            continue;
+        }
+        bumper.flash -= time.delta_seconds();
+        if let Some(material) = materials.get_mut(material) {
+            material.base_color = if bumper.flash > 0.0 {
+                Bumper::FLASH_COLOR
+            } else {
+                Bumper::COLOR
+            };
+        }
+    }
+}
@@ -3,11 +3,14 @@ use bevy::{
     prelude::*,
 };
 
+use crate::{config::PinballConfig, GameState};
+
 pub struct CameraPlugin;
 
 impl Plugin for CameraPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(Startup, spawn_camera)
+            .add_systems(OnEnter(GameState::Loaded), frame_board)
             .add_systems(Update, pan_orbit_camera);
     }
 }
@@ -46,6 +49,27 @@ fn spawn_camera(mut commands: Commands) {
     ));
 }
 
+/// Place the camera so the whole board is visible. It is only done when the board size
+/// changes, so the camera is not moved when the level is reloaded.
+fn frame_board(
+    pinball_config: Res<PinballConfig>,
+    mut framed_size: Local<Option<Vec3>>,
+    mut query: Query<(&mut PanOrbitCamera, &mut Transform)>,
+) {
+    let size = pinball_config.board.size;
+    if *framed_size == Some(size) {
+        return;
+    }
+    *framed_size = Some(size);
+
+    let translation = Vec3::new(0.0, size.z / 8.0, size.z / 2.0 + size.x.max(size.z) * 0.55);
+    for (mut pan_orbit, mut transform) in query.iter_mut() {
+        *transform = Transform::from_translation(translation).looking_at(Vec3::ZERO, Vec3::Y);
+        pan_orbit.focus = Vec3::ZERO;
+        pan_orbit.radius = translation.length();
+    }
+}
+
 /// Pan the camera with [CTRL] + middle mouse click, zoom with [CTRL] + scroll wheel,
 /// orbit with [CTRL] + right mouse click.
 fn pan_orbit_camera(
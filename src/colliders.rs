@@ -0,0 +1,245 @@
+use std::collections::HashMap;
+
+use bevy::{gltf::GltfExtras, prelude::*};
+use bevy_rapier3d::prelude::*;
+
+use crate::{
+    extras::{ColliderShape, NodeExtras},
+    score::Scoring,
+    Error,
+};
+
+/// Name of the glTF node grouping the meshes only used as colliders
+const COLLIDERS_NODE: &str = "Colliders";
+
+/// Marker of the static colliders spawned from the glTF scene
+#[derive(Component)]
+pub struct SceneCollider;
+
+/// A collider extracted from the glTF scene
+#[derive(Clone)]
+pub struct SceneColliderDef {
+    pub collider: Collider,
+    /// transform relative to the scene root
+    pub transform: Transform,
+    /// name of the glTF node
+    pub name: String,
+    pub extras: NodeExtras,
+}
+
+/// Create a collider for each mesh of the scene, with its transform relative to the scene root.
+///
+/// Meshes under the [COLLIDERS_NODE] node are only used for physics : they get a convex hull
+/// collider and are removed from the scene so they are not rendered. The other meshes are
+/// kept and get a trimesh collider, unless their extras set `"collider": "none"` like the
+/// board mesh, which would double the generated board collider.
+///
+/// The shape and physics properties can be overriden with the node extras (see [NodeExtras]).
+pub fn get_scene_colliders(
+    meshes: &Assets<Mesh>,
+    world: &mut World,
+) -> Result<Vec<SceneColliderDef>, Error> {
+    let mut colliders = Vec::new();
+    let mut hidden = Vec::new();
+
+    let mut meshes_q = world.query::<(Entity, &Handle<Mesh>)>();
+    for (entity, mesh_handle) in meshes_q.iter(world) {
+        let collider_only = has_ancestor_named(world, entity, COLLIDERS_NODE);
+        if collider_only {
+            hidden.push(entity);
+        }
+
+        let extras = node_extras(world, entity)?;
+        let default_shape = if collider_only {
+            ColliderShape::Convex
+        } else {
+            ColliderShape::Trimesh
+        };
+        let shape = extras.collider.unwrap_or(default_shape);
+        if shape == ColliderShape::None {
+            continue;
+        }
+
+        let node = node_name(world, entity);
+        let mesh = meshes
+            .get(mesh_handle)
+            .ok_or_else(|| Error::MissingGltfMesh { node: node.clone() })?;
+        let mut transform = scene_transform(world, entity);
+        let collider = match shape {
+            ColliderShape::Convex => {
+                Collider::from_bevy_mesh(mesh, &ComputedColliderShape::ConvexHull)
+            }
+            ColliderShape::Trimesh => {
+                Collider::from_bevy_mesh(mesh, &ComputedColliderShape::TriMesh)
+            }
+            ColliderShape::Cuboid => mesh.compute_aabb().map(|aabb| {
+                transform = transform * Transform::from_translation(aabb.center.into());
+                let half = Vec3::from(aabb.half_extents);
+                Collider::cuboid(half.x, half.y, half.z)
+            }),
+            ColliderShape::None => None,
+        }
+        .ok_or_else(|| Error::ColliderBuildFailed { node: node.clone() })?;
+
+        colliders.push(SceneColliderDef {
+            collider,
+            transform,
+            name: node,
+            extras,
+        });
+    }
+
+    for entity in hidden {
+        world.entity_mut(entity).remove::<Handle<Mesh>>();
+    }
+
+    Ok(colliders)
+}
+
+/// Spawn the colliders as fixed bodies, children of the given builder.
+///
+/// `scores` gives the points of the nodes by name, overriding the `score` of their extras.
+pub fn spawn_scene_colliders(
+    builder: &mut ChildBuilder,
+    colliders: &[SceneColliderDef],
+    scores: &HashMap<String, u32>,
+) {
+    for def in colliders.iter() {
+        let mut entity = builder.spawn((
+            Name::new(def.name.clone()),
+            SceneCollider,
+            TransformBundle::from_transform(def.transform),
+            RigidBody::Fixed,
+            def.collider.clone(),
+        ));
+        def.extras.insert(&mut entity);
+        if let Some(&points) = scores.get(&def.name) {
+            entity.insert(Scoring(points));
+        }
+    }
+}
+
+/// Get the extras of the nearest entity, starting from the given one and walking up
+/// its ancestors
+fn node_extras(world: &World, entity: Entity) -> Result<NodeExtras, Error> {
+    let mut current = Some(entity);
+    while let Some(e) = current {
+        if let Some(extras) = world.get::<GltfExtras>(e) {
+            let name = world.get::<Name>(e).map(Name::as_str).unwrap_or_default();
+            return NodeExtras::parse(name, extras);
+        }
+        current = world.get::<Parent>(e).map(|p| p.get());
+    }
+    Ok(NodeExtras::default())
+}
+
+/// Get the name of the glTF node of a mesh entity, which is its parent
+fn node_name(world: &World, entity: Entity) -> String {
+    world
+        .get::<Parent>(entity)
+        .and_then(|p| world.get::<Name>(p.get()))
+        .or_else(|| world.get::<Name>(entity))
+        .map(|n| n.to_string())
+        .unwrap_or_default()
+}
+
+/// Check if the entity, or one of its ancestors, has the given name
+fn has_ancestor_named(world: &World, entity: Entity, name: &str) -> bool {
+    let mut current = Some(entity);
+    while let Some(e) = current {
+        if world.get::<Name>(e).is_some_and(|n| n.as_str() == name) {
+            return true;
+        }
+        current = world.get::<Parent>(e).map(|p| p.get());
+    }
+    false
+}
+
+/// Compute the transform of the entity relative to the scene root, as the scene world
+/// doesn't propagate the [GlobalTransform]
+fn scene_transform(world: &World, entity: Entity) -> Transform {
+    let mut transform = world.get::<Transform>(entity).copied().unwrap_or_default();
+    let mut current = world.get::<Parent>(entity).map(|p| p.get());
+    while let Some(e) = current {
+        if let Some(parent_transform) = world.get::<Transform>(e) {
+            transform = parent_transform.mul_transform(transform);
+        }
+        current = world.get::<Parent>(e).map(|p| p.get());
+    }
+    transform
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(world: &mut World, name: &str, extras: Option<&str>, parent: Option<Entity>) -> Entity {
+        let mut entity = world.spawn((Name::new(name.to_string()), Transform::default()));
+        if let Some(value) = extras {
+            entity.insert(GltfExtras {
+                value: value.to_string(),
+            });
+        }
+        let entity = entity.id();
+        if let Some(parent) = parent {
+            world.entity_mut(parent).push_children(&[entity]);
+        }
+        entity
+    }
+
+    #[test]
+    fn extras_of_the_nearest_node() {
+        let mut world = World::new();
+        let board = node(&mut world, "Board", Some(r#"{"collider": "none"}"#), None);
+        let board_mesh = node(&mut world, "Board.mesh", None, Some(board));
+        let border = node(
+            &mut world,
+            "Border",
+            Some(r#"{"collider": "trimesh"}"#),
+            Some(board),
+        );
+        let border_mesh = node(&mut world, "Border.mesh", None, Some(border));
+        let other = node(&mut world, "Other", None, None);
+
+        let collider = |entity| node_extras(&world, entity).unwrap().collider;
+        assert_eq!(collider(board_mesh), Some(ColliderShape::None));
+        assert_eq!(collider(border_mesh), Some(ColliderShape::Trimesh));
+        assert_eq!(collider(other), None);
+    }
+
+    #[test]
+    fn invalid_extras_of_an_ancestor() {
+        let mut world = World::new();
+        let parent = node(&mut world, "Parent", Some(r#"{"sensor": 3}"#), None);
+        let mesh = node(&mut world, "Mesh", None, Some(parent));
+        assert!(matches!(
+            node_extras(&world, mesh),
+            Err(Error::InvalidGltfExtras { node, .. }) if node == "Parent"
+        ));
+    }
+
+    #[test]
+    fn meshes_under_the_colliders_node() {
+        let mut world = World::new();
+        let colliders = node(&mut world, COLLIDERS_NODE, None, None);
+        let cube = node(&mut world, "Cube", None, Some(colliders));
+        let cube_mesh = node(&mut world, "Cube.mesh", None, Some(cube));
+        let board = node(&mut world, "Board", None, None);
+        assert!(has_ancestor_named(&world, cube_mesh, COLLIDERS_NODE));
+        assert!(has_ancestor_named(&world, colliders, COLLIDERS_NODE));
+        assert!(!has_ancestor_named(&world, board, COLLIDERS_NODE));
+    }
+
+    #[test]
+    fn transform_relative_to_the_scene_root() {
+        let mut world = World::new();
+        let parent = node(&mut world, "Parent", None, None);
+        let child = node(&mut world, "Child", None, Some(parent));
+        *world.get_mut::<Transform>(parent).unwrap() =
+            Transform::from_xyz(1.0, 0.0, 0.0).with_scale(Vec3::splat(2.0));
+        *world.get_mut::<Transform>(child).unwrap() = Transform::from_xyz(0.0, 0.0, 3.0);
+        let transform = scene_transform(&world, child);
+        assert_eq!(transform.translation, Vec3::new(1.0, 0.0, 6.0));
+        assert_eq!(transform.scale, Vec3::splat(2.0));
+    }
+}
@@ -0,0 +1,227 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::PinballConfig,
+    rules::{RuleEvents, Trigger},
+    score::ScoreEvent,
+    GameState,
+};
+
+pub struct ComboPlugin;
+
+impl Plugin for ComboPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Combos>()
+            .add_event::<ComboEvent>()
+            .add_systems(OnEnter(GameState::Loaded), reset_combos)
+            .add_systems(
+                Update,
+                (track_combos, expire_combos)
+                    .chain()
+                    .run_if(in_state(GameState::Loaded)),
+            );
+    }
+}
+
+/// A sequence of shots to make in order, each one in the time window of the previous one.
+///
+/// Making the combo again before the window is over continues the streak, and the bonus
+/// is multiplied by its length.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ComboConfig {
+    pub id: String,
+    pub shots: Vec<Trigger>,
+    /// maximum time between two shots, in seconds
+    #[serde(default = "ComboConfig::default_window")]
+    pub window: f32,
+    /// points given for the combo, multiplied by the streak
+    #[serde(default)]
+    pub score: u32,
+}
+
+impl ComboConfig {
+    fn default_window() -> f32 {
+        3.0
+    }
+
+    /// Check the shots and window of the combo, returning the reason if invalid
+    pub fn validate(&self) -> Result<(), String> {
+        if self.shots.len() < 2 {
+            return Err("at least 2 shots are needed".into());
+        }
+        if self.window <= 0.0 {
+            return Err("window should be positive".into());
+        }
+        Ok(())
+    }
+}
+
+/// Sent when a combo is made
+#[derive(Event)]
+pub struct ComboEvent {
+    pub id: String,
+    /// number of times the combo was made in a row, starting at 1
+    pub streak: u32,
+    pub points: u32,
+}
+
+#[derive(Clone, Debug, Default)]
+struct ComboState {
+    /// index of the next shot of the sequence
+    next: usize,
+    /// time left to make the next shot, in seconds
+    timer: f32,
+    streak: u32,
+}
+
+impl ComboState {
+    /// Advance the combo with a shot, returning the streak when the combo is made.
+    /// The shots which are not part of the combo don't break it.
+    fn shot(&mut self, config: &ComboConfig, trigger: &Trigger) -> Option<u32> {
+        if config.shots.get(self.next) == Some(trigger) {
+            self.next += 1;
+        } else if config.shots.first() == Some(trigger) {
+            // a new try
+            self.next = 1;
+        } else {
+            return None;
+        }
+        self.timer = config.window;
+        if self.next < config.shots.len() {
+            return None;
+        }
+        self.next = 0;
+        self.streak += 1;
+        Some(self.streak)
+    }
+
+    /// Count down the time left to make the next shot, returning true if the combo is broken
+    fn tick(&mut self, delta: f32) -> bool {
+        if self.timer <= 0.0 {
+            return false;
+        }
+        self.timer -= delta;
+        if self.timer > 0.0 {
+            return false;
+        }
+        *self = ComboState::default();
+        true
+    }
+}
+
+/// State of the combos of the level, in the order of [PinballConfig::combos]
+#[derive(Default, Resource)]
+pub struct Combos(Vec<ComboState>);
+
+impl Combos {
+    /// Longest streak of the combos in progress
+    pub fn streak(&self) -> u32 {
+        self.0.iter().map(|combo| combo.streak).max().unwrap_or(0)
+    }
+}
+
+fn reset_combos(mut combos: ResMut<Combos>, pinball_config: Res<PinballConfig>) {
+    combos.0 = vec![ComboState::default(); pinball_config.combos.len()];
+}
+
+/// Advance the combos with the shots of the frame
+fn track_combos(
+    mut combos: ResMut<Combos>,
+    pinball_config: Res<PinballConfig>,
+    mut events: RuleEvents,
+    mut combo_events: EventWriter<ComboEvent>,
+    mut score_events: EventWriter<ScoreEvent>,
+) {
+    for trigger in events.triggers() {
+        for (config, combo) in pinball_config.combos.iter().zip(combos.0.iter_mut()) {
+            let Some(streak) = combo.shot(config, &trigger) else {
+                continue;
+            };
+            let points = config.score.saturating_mul(streak);
+            combo_events.send(ComboEvent {
+                id: config.id.clone(),
+                streak,
+                points,
+            });
+            score_events.send(ScoreEvent {
+                points,
+                source: None,
+            });
+        }
+    }
+}
+
+/// Break the combos whose next shot was not made in time.
+///
+/// Counting down the timers doesn't change the combos, only breaking them does.
+fn expire_combos(mut combos: ResMut<Combos>, time: Res<Time>) {
+    let mut broken = false;
+    for combo in combos.bypass_change_detection().0.iter_mut() {
+        broken |= combo.tick(time.delta_seconds());
+    }
+    if broken {
+        combos.set_changed();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn combo() -> ComboConfig {
+        ComboConfig {
+            id: "test".into(),
+            shots: vec![
+                Trigger::Ramp("right".into()),
+                Trigger::Switch("lane".into()),
+            ],
+            window: 2.0,
+            score: 1000,
+        }
+    }
+
+    #[test]
+    fn shots_in_order_make_the_combo() {
+        let config = combo();
+        let mut state = ComboState::default();
+        assert_eq!(state.shot(&config, &config.shots[1]), None);
+        assert_eq!(state.shot(&config, &config.shots[0]), None);
+        // other shots don't break it
+        assert_eq!(state.shot(&config, &Trigger::Bumper), None);
+        assert_eq!(state.shot(&config, &config.shots[1]), Some(1));
+    }
+
+    #[test]
+    fn first_shot_starts_a_new_try() {
+        let config = combo();
+        let mut state = ComboState::default();
+        state.shot(&config, &config.shots[0]);
+        assert_eq!(state.shot(&config, &config.shots[0]), None);
+        assert_eq!(state.shot(&config, &config.shots[1]), Some(1));
+    }
+
+    #[test]
+    fn streak_goes_on_in_the_window() {
+        let config = combo();
+        let mut state = ComboState::default();
+        for streak in 1..=3 {
+            state.shot(&config, &config.shots[0]);
+            assert!(!state.tick(1.5));
+            assert_eq!(state.shot(&config, &config.shots[1]), Some(streak));
+            assert!(!state.tick(1.5));
+        }
+    }
+
+    #[test]
+    fn late_shot_breaks_the_combo() {
+        let config = combo();
+        let mut state = ComboState::default();
+        state.shot(&config, &config.shots[0]);
+        assert!(!state.tick(1.5));
+        assert!(state.tick(1.0));
+        assert_eq!(state.shot(&config, &config.shots[1]), None);
+        // broken once, then idle
+        assert!(!state.tick(1.0));
+    }
+}
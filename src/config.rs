@@ -1,39 +1,464 @@
-use bevy::prelude::*;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+    utils::BoxedFuture,
+};
 use bevy_rapier3d::dynamics::{GenericJoint, RevoluteJointBuilder};
 use serde::{Deserialize, Serialize};
 
-use crate::paddle::{Paddle, PaddleType};
+use crate::{
+    actuator::ActuatorConfig,
+    ball_save::BallSaveConfig,
+    bumper::BumperConfig,
+    combo::ComboConfig,
+    drain::DrainConfig,
+    error::{GameError, LoadFailure},
+    input::{Action, InputConfig},
+    lamp::LampConfig,
+    magnet::MagnetConfig,
+    paddle::{Paddle, PaddleType},
+    plunger::PlungerConfig,
+    ramp::RampConfig,
+    rules::{ModeConfig, RuleAction, Trigger},
+    saucer::SaucerConfig,
+    slingshot::SlingshotConfig,
+    spinner::SpinnerConfig,
+    switch::SwitchConfig,
+    target::TargetBankConfig,
+    tilt::TiltConfig,
+    Error, GameAssets, GameState,
+};
+
+pub struct ConfigPlugin;
+
+impl Plugin for ConfigPlugin {
+    fn build(&self, app: &mut App) {
+        let load_error = LevelLoadError::default();
+        app.init_asset::<PinballConfig>()
+            .register_asset_loader(PinballConfigLoader {
+                load_error: load_error.clone(),
+            })
+            .insert_resource(load_error)
+            .insert_resource(LevelPath::from_args())
+            .add_systems(OnEnter(GameState::Loading), load_level)
+            .add_systems(
+                Update,
+                check_level_loaded
+                    .run_if(in_state(GameState::Loading))
+                    .run_if(not(resource_exists::<PinballConfig>())),
+            )
+            .add_systems(
+                Update,
+                (
+                    check_level_reload_failed.run_if(not(in_state(GameState::Loading))),
+                    reload_level,
+                ),
+            );
+    }
+}
+
+/// Path of the level file, relative to the `assets` folder.
+///
+/// It can be selected on the command line : `pinball --level assets/tables/foo.json`
+#[derive(Resource)]
+pub struct LevelPath(pub String);
+
+impl LevelPath {
+    const DEFAULT: &'static str = "level.json";
+
+    fn from_args() -> Self {
+        let mut path = Self::DEFAULT.to_string();
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            if arg == "--level" {
+                if let Some(level) = args.next() {
+                    path = level;
+                }
+            }
+        }
+        match path.strip_prefix("assets/") {
+            Some(stripped) => Self(stripped.to_string()),
+            None => Self(path),
+        }
+    }
+}
 
-#[derive(Debug, Resource, Deserialize, Serialize)]
+/// The level : loaded as an asset from the [LevelPath] file, then inserted as a resource
+/// once validated
+#[derive(Asset, Clone, Debug, Resource, Deserialize, Serialize, TypePath)]
 pub struct PinballConfig {
     pub board: BoardConfig,
     pub paddles: Vec<PaddleConfig>,
+    #[serde(default)]
+    pub slingshots: Vec<SlingshotConfig>,
+    #[serde(default)]
+    pub plunger: Option<PlungerConfig>,
+    #[serde(default)]
+    pub drain: Option<DrainConfig>,
+    #[serde(default)]
+    pub bumpers: Vec<BumperConfig>,
+    #[serde(default)]
+    pub targets: Vec<TargetBankConfig>,
+    #[serde(default)]
+    pub switches: Vec<SwitchConfig>,
+    #[serde(default)]
+    pub spinners: Vec<SpinnerConfig>,
+    #[serde(default)]
+    pub ramps: Vec<RampConfig>,
+    #[serde(default)]
+    pub saucers: Vec<SaucerConfig>,
+    #[serde(default)]
+    pub magnets: Vec<MagnetConfig>,
+    #[serde(default)]
+    pub actuators: Vec<ActuatorConfig>,
+    #[serde(default)]
+    pub lamps: Vec<LampConfig>,
+    /// number of balls per game
+    #[serde(default = "PinballConfig::default_balls")]
+    pub balls: u32,
+    #[serde(default)]
+    pub ball_save: Option<BallSaveConfig>,
+    /// maximum number of extra balls per game
+    #[serde(default)]
+    pub extra_balls: u32,
+    #[serde(default)]
+    pub inputs: InputConfig,
+    #[serde(default)]
+    pub tilt: TiltConfig,
+    /// rules of the table
+    #[serde(default)]
+    pub modes: Vec<ModeConfig>,
+    #[serde(default)]
+    pub combos: Vec<ComboConfig>,
+    /// rules script, relative to the `assets` folder
+    #[serde(default)]
+    pub script: Option<String>,
+    /// points given by the glTF nodes, by name
+    #[serde(default)]
+    pub scores: HashMap<String, u32>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+impl PinballConfig {
+    fn default_balls() -> u32 {
+        3
+    }
+
+    /// Start position of the ball : in the plunger lane if any, else `board.ball`
+    pub fn ball_start(&self) -> Vec3 {
+        match &self.plunger {
+            Some(plunger) => self
+                .board
+                .transform()
+                .transform_point(plunger.ball_pos(&self.board)),
+            None => self.board.ball,
+        }
+    }
+
+    /// Check that the board has a valid size, that all the elements are on it and that the
+    /// rules only use existing elements
+    fn validate(&self) -> Result<(), Error> {
+        let board = &self.board;
+        let size = board.size;
+        if size.x <= 0.0 || size.y <= 0.0 || size.z <= 0.0 {
+            return Err(Error::InvalidBoard(format!(
+                "size {size} should be positive"
+            )));
+        }
+        for (index, paddle) in self.paddles.iter().enumerate() {
+            paddle
+                .validate(board)
+                .map_err(|reason| Error::InvalidPaddle { index, reason })?;
+        }
+        for (index, slingshot) in self.slingshots.iter().enumerate() {
+            slingshot
+                .validate(board)
+                .map_err(|reason| Error::InvalidSlingshot { index, reason })?;
+        }
+        for (index, bumper) in self.bumpers.iter().enumerate() {
+            bumper
+                .validate(board)
+                .map_err(|reason| Error::InvalidBumper { index, reason })?;
+        }
+        for (index, bank) in self.targets.iter().enumerate() {
+            bank.validate(board)
+                .map_err(|reason| Error::InvalidTargetBank { index, reason })?;
+        }
+        for ramp in self.ramps.iter() {
+            ramp.validate(board).map_err(|reason| Error::InvalidRamp {
+                id: ramp.id.clone(),
+                reason,
+            })?;
+        }
+        for switch in self.switches.iter() {
+            switch
+                .validate(board)
+                .map_err(|reason| Error::InvalidSwitch {
+                    id: switch.id.clone(),
+                    reason,
+                })?;
+        }
+        for (index, spinner) in self.spinners.iter().enumerate() {
+            spinner
+                .validate(board)
+                .map_err(|reason| Error::InvalidSpinner { index, reason })?;
+        }
+        for saucer in self.saucers.iter() {
+            saucer
+                .validate(board)
+                .map_err(|reason| Error::InvalidSaucer {
+                    id: saucer.id.clone(),
+                    reason,
+                })?;
+        }
+        for magnet in self.magnets.iter() {
+            magnet
+                .validate(board)
+                .map_err(|reason| Error::InvalidMagnet {
+                    id: magnet.id.clone(),
+                    reason,
+                })?;
+        }
+        for actuator in self.actuators.iter() {
+            actuator
+                .validate(board)
+                .map_err(|reason| Error::InvalidActuator {
+                    id: actuator.id.clone(),
+                    reason,
+                })?;
+        }
+        for lamp in self.lamps.iter() {
+            lamp.validate(board).map_err(|reason| Error::InvalidLamp {
+                id: lamp.id.clone(),
+                reason,
+            })?;
+        }
+        if let Some(plunger) = &self.plunger {
+            plunger.validate(board).map_err(Error::InvalidPlunger)?;
+        }
+        if let Some(drain) = &self.drain {
+            drain.validate(board).map_err(Error::InvalidDrain)?;
+        }
+        for mode in self.modes.iter() {
+            mode.validate()
+                .and_then(|()| self.check_mode(mode))
+                .map_err(|reason| Error::InvalidMode {
+                    id: mode.id.clone(),
+                    reason,
+                })?;
+        }
+        for combo in self.combos.iter() {
+            combo
+                .validate()
+                .and_then(|()| {
+                    combo
+                        .shots
+                        .iter()
+                        .try_for_each(|shot| self.check_trigger(shot))
+                })
+                .map_err(|reason| Error::InvalidCombo {
+                    id: combo.id.clone(),
+                    reason,
+                })?;
+        }
+        Ok(())
+    }
+
+    /// Check that the elements used by the triggers and actions of a mode exist
+    fn check_mode(&self, mode: &ModeConfig) -> Result<(), String> {
+        let goals = mode.goals.iter().map(|goal| &goal.trigger);
+        for trigger in std::iter::once(&mode.start).chain(goals) {
+            self.check_trigger(trigger)?;
+        }
+        for action in mode
+            .on_start
+            .iter()
+            .chain(&mode.rewards)
+            .chain(&mode.on_fail)
+        {
+            self.check_action(action)?;
+        }
+        Ok(())
+    }
+
+    /// Check that the element of a rule trigger exists, returning the reason if not
+    fn check_trigger(&self, trigger: &Trigger) -> Result<(), String> {
+        let (kind, id, exists) = match trigger {
+            Trigger::Switch(id) => ("switch", id, self.switches.iter().any(|s| &s.id == id)),
+            Trigger::Ramp(id) => ("ramp", id, self.ramps.iter().any(|r| &r.id == id)),
+            Trigger::Bank(id) => ("target bank", id, self.targets.iter().any(|b| &b.id == id)),
+            Trigger::Saucer(id) => ("saucer", id, self.saucers.iter().any(|s| &s.id == id)),
+            Trigger::Mode(id) => ("mode", id, self.modes.iter().any(|m| &m.id == id)),
+            _ => return Ok(()),
+        };
+        if exists {
+            Ok(())
+        } else {
+            Err(format!("unknown {kind} '{id}'"))
+        }
+    }
+
+    /// Check that the element of a rule action exists, returning the reason if not
+    fn check_action(&self, action: &RuleAction) -> Result<(), String> {
+        let (kind, id, exists) = match action {
+            RuleAction::Magnet { id, .. } => {
+                ("magnet", id, self.magnets.iter().any(|m| &m.id == id))
+            }
+            RuleAction::Actuator { id, .. } => {
+                ("actuator", id, self.actuators.iter().any(|a| &a.id == id))
+            }
+            RuleAction::Lamp { id, .. } => ("lamp", id, self.lamps.iter().any(|l| &l.id == id)),
+            RuleAction::ResetBank(id) => {
+                ("target bank", id, self.targets.iter().any(|b| &b.id == id))
+            }
+            RuleAction::EjectSaucer(id) => ("saucer", id, self.saucers.iter().any(|s| &s.id == id)),
+            _ => return Ok(()),
+        };
+        if exists {
+            Ok(())
+        } else {
+            Err(format!("unknown {kind} '{id}'"))
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct BoardConfig {
     pub size: Vec3,
     pub angle: f32,
     pub ball: Vec3,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+impl BoardConfig {
+    /// Height of the board surface, relative to the board center
+    pub fn surface(&self) -> f32 {
+        self.size.y / 2.0
+    }
+
+    /// Check that a rectangle centered on `pos`, with the given half sizes, is on the board
+    pub fn contains(&self, pos: Vec3, half_x: f32, half_z: f32) -> bool {
+        let (hx, hz) = (self.size.x / 2.0, self.size.z / 2.0);
+        pos.x - half_x >= -hx
+            && pos.x + half_x <= hx
+            && pos.z - half_z >= -hz
+            && pos.z + half_z <= hz
+    }
+
+    /// Transform of the board, placing its surface at the origin
+    pub fn transform(&self) -> Transform {
+        Transform::from_xyz(0.0, -self.surface(), 0.0)
+            .with_rotation(Quat::from_rotation_x(self.angle))
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct PaddleConfig {
     pub pos: Vec3,
     pub ptype: PaddleType,
+    #[serde(flatten)]
+    pub settings: PaddleSettings,
+}
+
+/// Optional settings of a paddle. The angles are the ones of a left paddle, they are
+/// mirrored for a right paddle.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PaddleSettings {
+    pub length: f32,
+    pub thickness: f32,
+    /// motor target angle when the action is released, in radians
+    pub rest_angle: f32,
+    /// motor target angle when the action is pressed, in radians
+    pub active_angle: f32,
+    pub stiffness: f32,
+    pub damping: f32,
+    pub mass: f32,
+    /// input action, defaults to "left" or "right" depending on the paddle type
+    pub action: Option<String>,
+}
+
+impl Default for PaddleSettings {
+    fn default() -> Self {
+        PaddleSettings {
+            length: 5.0,
+            thickness: 2.0,
+            rest_angle: -0.4,
+            active_angle: 0.4,
+            stiffness: 5000.0,
+            damping: 1.0,
+            mass: 10.0,
+            action: None,
+        }
+    }
 }
 
 impl PaddleConfig {
+    pub fn size(&self) -> Vec3 {
+        Vec3::new(
+            self.settings.length,
+            Paddle::HEIGHT,
+            self.settings.thickness,
+        )
+    }
+
+    pub fn action(&self) -> &str {
+        match (&self.settings.action, self.ptype) {
+            (Some(action), _) => action,
+            (None, PaddleType::Left) => Action::LEFT,
+            (None, PaddleType::Right) => Action::RIGHT,
+        }
+    }
+
+    /// Motor target angles `(rest, active)`, mirrored for a right paddle
+    pub fn angles(&self) -> (f32, f32) {
+        let (rest, active) = (self.settings.rest_angle, self.settings.active_angle);
+        match self.ptype {
+            PaddleType::Left => (rest, active),
+            PaddleType::Right => (-rest, -active),
+        }
+    }
+
     fn x_offset(&self) -> f32 {
+        let hx = self.settings.length / 2.0;
         match self.ptype {
-            PaddleType::Left => -Paddle::hx(),
-            PaddleType::Right => Paddle::hx(),
+            PaddleType::Left => -hx,
+            PaddleType::Right => hx,
         }
     }
 
-    pub fn transform(&self) -> Transform {
+    /// Range of the paddle on the x axis, when at rest
+    fn x_range(&self) -> (f32, f32) {
+        let length = self.settings.length;
+        match self.ptype {
+            PaddleType::Left => (self.pos.x, self.pos.x + length),
+            PaddleType::Right => (self.pos.x - length, self.pos.x),
+        }
+    }
+
+    /// Check the settings of the paddle and that it is on the board, returning the reason if not
+    fn validate(&self, board: &BoardConfig) -> Result<(), String> {
+        let settings = &self.settings;
+        if settings.length <= 0.0 || settings.thickness <= 0.0 || settings.mass <= 0.0 {
+            return Err("length, thickness and mass should be positive".into());
+        }
+        if settings.stiffness < 0.0 || settings.damping < 0.0 {
+            return Err("stiffness and damping should not be negative".into());
+        }
+        let (min_x, max_x) = self.x_range();
+        let center = Vec3::new((min_x + max_x) / 2.0, 0.0, self.pos.z);
+        if !board.contains(center, settings.length / 2.0, settings.thickness / 2.0) {
+            return Err(format!("paddle at {} is out of the board", self.pos));
+        }
+        Ok(())
+    }
+
+    pub fn transform(&self, board: &BoardConfig) -> Transform {
         let x = self.pos.x - self.x_offset();
-        let y = Paddle::hy() + Paddle::SPACE;
+        let y = board.surface() + Paddle::hy() + Paddle::SPACE;
         let z = self.pos.z;
         Transform::from_xyz(x, y, z)
     }
@@ -42,9 +467,9 @@ impl PaddleConfig {
     /// - anchor1 (parent position): position on the ground (the `pos` param)
     /// - anchor2 (paddle position): position of the point of rotation
     ///
-    pub fn joint(&self) -> impl Into<GenericJoint> {
-        // parent entity anchor position is self.pos, forced y at 0
-        let parent_pos = Vec3::new(self.pos.x, 0.0, self.pos.z);
+    pub fn joint(&self, board: &BoardConfig) -> impl Into<GenericJoint> {
+        // parent entity anchor position is self.pos, forced y at the board surface
+        let parent_pos = Vec3::new(self.pos.x, board.surface(), self.pos.z);
         // paddle anchor position is at x border, at [SPACE] height
         let paddle_pos = Vec3::new(self.x_offset(), -Paddle::SPACE - Paddle::hy(), 0.0);
         info!("Joint : parent_pos = {parent_pos}, paddle_pos = {paddle_pos}");
@@ -54,17 +479,96 @@ impl PaddleConfig {
     }
 }
 
-pub fn load_config() -> PinballConfig {
-    let content = include_str!("../assets/level.json");
-    let config = serde_json::from_str(content).expect("can't deserialize level");
-    info!("Load config : {config:?}");
-    config
+/// Last error of the [PinballConfigLoader], as the asset server only reports a failed
+/// load state, seen by a [LoadFailure]
+#[derive(Clone, Default, Resource)]
+struct LevelLoadError(Arc<Mutex<Option<Error>>>);
+
+impl LevelLoadError {
+    fn set(&self, error: Error) {
+        if let Ok(mut last) = self.0.lock() {
+            *last = Some(error);
+        }
+    }
+
+    /// Last error, defaulting to a generic load error of the level
+    fn get(&self, level_path: &LevelPath) -> Error {
+        self.0
+            .lock()
+            .ok()
+            .and_then(|last| last.clone())
+            .unwrap_or_else(|| Error::LoadLevel(level_path.0.clone()))
+    }
+}
+
+struct PinballConfigLoader {
+    load_error: LevelLoadError,
+}
+
+impl AssetLoader for PinballConfigLoader {
+    type Asset = PinballConfig;
+    type Settings = ();
+    type Error = Error;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<PinballConfig, Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            let result = match reader.read_to_end(&mut bytes).await {
+                Ok(_) => serde_json::from_slice(&bytes).map_err(Error::from),
+                Err(_) => Err(Error::LoadLevel(load_context.path().display().to_string())),
+            };
+            if let Err(e) = &result {
+                self.load_error.set(e.clone());
+            }
+            result
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["json"]
+    }
+}
+
+fn load_level(
+    asset_server: Res<AssetServer>,
+    level_path: Res<LevelPath>,
+    mut game_assets: ResMut<GameAssets>,
+) {
+    game_assets.level = asset_server.load(level_path.0.clone());
+}
+
+// check if the level is loaded and if so, insert it as a resource
+fn check_level_loaded(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    configs: Res<Assets<PinballConfig>>,
+    game_assets: Res<GameAssets>,
+    level_path: Res<LevelPath>,
+    load_error: Res<LevelLoadError>,
+    mut load_failure: Local<LoadFailure>,
+) {
+    if load_failure.check(&asset_server, &game_assets.level) {
+        commands.insert_resource(GameError(load_error.get(&level_path)));
+    } else if let Some(config) = configs.get(&game_assets.level) {
+        match config.validate() {
+            Ok(()) => {
+                info!("Load config : {config:?}");
+                commands.insert_resource(config.clone());
+            }
+            Err(e) => commands.insert_resource(GameError(e)),
+        }
+    }
 }
 
 pub fn test() {
     let config = PinballConfig {
         board: BoardConfig {
-            size: Vec3::new(40.0, 0.0, 40.0),
+            size: Vec3::new(40.0, 0.2, 40.0),
             ball: Vec3::new(15.0, 3.0, 0.0),
             angle: 0.1,
         },
@@ -72,13 +576,221 @@ pub fn test() {
             PaddleConfig {
                 pos: Vec3::new(12.0, 0.0, 18.0),
                 ptype: PaddleType::Right,
+                settings: PaddleSettings::default(),
             },
             PaddleConfig {
                 pos: Vec3::new(8.0, 0.0, 18.0),
                 ptype: PaddleType::Left,
+                settings: PaddleSettings::default(),
             },
         ],
+        slingshots: vec![],
+        plunger: None,
+        drain: None,
+        bumpers: vec![],
+        targets: vec![],
+        switches: vec![],
+        spinners: vec![],
+        ramps: vec![],
+        saucers: vec![],
+        magnets: vec![],
+        actuators: vec![],
+        lamps: vec![],
+        balls: 3,
+        ball_save: None,
+        extra_balls: 0,
+        inputs: InputConfig::default(),
+        tilt: TiltConfig::default(),
+        modes: vec![],
+        combos: vec![],
+        script: None,
+        scores: HashMap::new(),
     };
     let s = serde_json::to_string(&config).unwrap();
     info!("Config test : {s}");
 }
+
+// check if the hot reloaded level file can't be loaded, replacing a previous error
+fn check_level_reload_failed(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    game_assets: Res<GameAssets>,
+    level_path: Res<LevelPath>,
+    load_error: Res<LevelLoadError>,
+    mut load_failure: Local<LoadFailure>,
+) {
+    if load_failure.check(&asset_server, &game_assets.level) {
+        commands.insert_resource(GameError(load_error.get(&level_path)));
+    }
+}
+
+// when the level file is modified, insert the new level and go through the loading state
+// again to rebuild the board.
+// The events are read in all states, so the ones of the initial load are not seen as a reload
+fn reload_level(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<PinballConfig>>,
+    configs: Res<Assets<PinballConfig>>,
+    game_assets: Res<GameAssets>,
+    state: Res<State<GameState>>,
+    mut game_state: ResMut<NextState<GameState>>,
+) {
+    let level = &game_assets.level;
+    let changed = events
+        .read()
+        .any(|e| e.is_modified(level) || e.is_added(level));
+    if !changed || *state.get() == GameState::Loading {
+        return;
+    }
+    if let Some(config) = configs.get(&game_assets.level) {
+        match config.validate() {
+            Ok(()) => {
+                info!("Reload config : {config:?}");
+                commands.insert_resource(config.clone());
+                game_state.set(GameState::Loading);
+            }
+            Err(e) => commands.insert_resource(GameError(e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn level() -> PinballConfig {
+        serde_json::from_str(include_str!("../assets/level.json")).unwrap()
+    }
+
+    /// Reason of the error of an invalid level
+    fn reason(config: &PinballConfig) -> String {
+        match config.validate() {
+            Ok(()) => panic!("the level should be invalid"),
+            Err(e) => e.to_string(),
+        }
+    }
+
+    #[test]
+    fn level_is_valid() {
+        level().validate().unwrap();
+    }
+
+    #[test]
+    fn board_size_should_be_positive() {
+        let mut config = level();
+        config.board.size.z = 0.0;
+        assert!(matches!(config.validate(), Err(Error::InvalidBoard(_))));
+    }
+
+    #[test]
+    fn bumper_should_be_on_the_board() {
+        let mut config = level();
+        config.bumpers[1].pos = Vec3::new(19.0, 0.0, 0.0);
+        assert!(matches!(
+            config.validate(),
+            Err(Error::InvalidBumper { index: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn paddle_stiffness_should_not_be_negative() {
+        let mut config = level();
+        config.paddles[0].settings.stiffness = -1.0;
+        assert!(matches!(
+            config.validate(),
+            Err(Error::InvalidPaddle { index: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn whole_target_bank_should_be_on_the_board() {
+        let mut config = level();
+        // the center is on the board, not the last target
+        config.targets[0].pos = Vec3::new(18.0, 0.0, 0.0);
+        config.targets[0].angle = 0.0;
+        assert!(matches!(
+            config.validate(),
+            Err(Error::InvalidTargetBank { index: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn ramp_should_be_above_the_board() {
+        let mut config = level();
+        config.ramps[0].points[2].z = -25.0;
+        assert!(matches!(config.validate(), Err(Error::InvalidRamp { .. })));
+    }
+
+    #[test]
+    fn spinner_damping_should_not_be_negative() {
+        let mut config = level();
+        config.spinners[0].damping = -0.5;
+        assert!(matches!(
+            config.validate(),
+            Err(Error::InvalidSpinner { index: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn plunger_direction_should_be_along_the_board() {
+        let mut config = level();
+        if let Some(plunger) = &mut config.plunger {
+            plunger.direction = Vec3::Y;
+        }
+        assert!(matches!(config.validate(), Err(Error::InvalidPlunger(_))));
+    }
+
+    #[test]
+    fn drain_can_be_below_the_board() {
+        let mut config = level();
+        if let Some(drain) = &mut config.drain {
+            drain.pos.z = 20.5;
+        }
+        config.validate().unwrap();
+        if let Some(drain) = &mut config.drain {
+            drain.pos.z = 25.0;
+        }
+        assert!(matches!(config.validate(), Err(Error::InvalidDrain(_))));
+    }
+
+    #[test]
+    fn mode_triggers_should_exist() {
+        let mut config = level();
+        config.modes[0].start = Trigger::Bank("missing".into());
+        assert_eq!(
+            reason(&config),
+            "invalid mode 'ramp_frenzy': unknown target bank 'missing'"
+        );
+    }
+
+    #[test]
+    fn mode_actions_should_exist() {
+        let mut config = level();
+        config.modes[1].on_start.push(RuleAction::Lamp {
+            id: "missing".into(),
+            on: true,
+        });
+        assert_eq!(
+            reason(&config),
+            "invalid mode 'lanes': unknown lamp 'missing'"
+        );
+    }
+
+    #[test]
+    fn combo_shots_should_exist() {
+        let mut config = level();
+        config.combos[0].shots[1] = Trigger::Switch("missing".into());
+        assert_eq!(
+            reason(&config),
+            "invalid combo 'ramp_lane': unknown switch 'missing'"
+        );
+    }
+
+    #[test]
+    fn triggers_without_id_are_valid() {
+        let config = level();
+        assert!(config.check_trigger(&Trigger::Bumper).is_ok());
+        assert!(config.check_action(&RuleAction::ExtraBall).is_ok());
+        assert!(config.check_trigger(&Trigger::Mode("lanes".into())).is_ok());
+    }
+}
@@ -1,7 +1,27 @@
 use bevy::{gltf::GltfExtras, prelude::*};
 use bevy_rapier3d::prelude::*;
 
-use crate::{config, paddle::Paddle, GameAssets, GameState};
+use crate::{
+    ball::{Ball, BallStateChanged},
+    ball_save::BallSaved,
+    bumper::BumperHit,
+    combo::ComboEvent,
+    config,
+    magnet::{BallGrabbed, BallReleased},
+    paddle::Paddle,
+    plunger::{BallLaunched, SkillShot},
+    ramp::RampEvent,
+    rules::{ModeCompleted, ModeFailed, ModeStarted},
+    saucer::{BallCaptured, BallEjected},
+    score::ScoreEvent,
+    spinner::SpinnerRevolution,
+    switch::SwitchEvent,
+    target::{BankComplete, TargetHit},
+    GameAssets, GameState,
+};
+
+#[cfg(debug_assertions)]
+use crate::{game::ExtraBall, multiball::StartMultiball};
 
 pub struct DebugPlugin;
 
@@ -12,21 +32,24 @@ impl Plugin for DebugPlugin {
                 bevy_inspector_egui::quick::WorldInspectorPlugin::new(),
                 RapierDebugRenderPlugin::default(),
             ))
-            .add_systems(OnEnter(GameState::Loading), (start_assets_loading,))
-            .add_systems(
-                Update,
-                (check_if_loaded,).run_if(in_state(GameState::Loading)),
-            )
             .add_systems(OnEnter(GameState::Loaded), (debug_gltf,))
-            .add_systems(Startup, (spawn_debug_ui, init_debug))
+            .add_systems(Startup, init_debug)
             .add_systems(FixedUpdate, debug)
-            .add_systems(Update, update_debug_ui);
+            .add_systems(
+                Update,
+                (
+                    log_ball_events,
+                    log_table_events,
+                    log_lane_events,
+                    log_mode_events,
+                ),
+            );
+        // cheat keys, only in the development builds
+        #[cfg(debug_assertions)]
+        app.add_systems(Update, debug_awards);
     }
 }
 
-#[derive(Component)]
-struct DebugUi;
-
 fn init_debug() {
     config::test();
 }
@@ -48,72 +71,116 @@ fn debug(
     // }
 }
 
-fn spawn_debug_ui(mut commands: Commands) {
-    commands
-        .spawn(NodeBundle {
-            style: Style {
-                width: Val::Percent(100.0),
-                height: Val::Px(80.0),
-                justify_content: JustifyContent::SpaceBetween,
-                ..default()
-            },
-            ..default()
-        })
-        .with_children(|parent| {
-            parent.spawn((
-                DebugUi,
-                TextBundle::from_section(
-                    "Text Example",
-                    TextStyle {
-                        // font: asset_server.load("fonts/FiraSans-Bold.ttf"),
-                        font_size: 10.0,
-                        ..default()
-                    },
-                )
-                .with_style(Style {
-                    margin: UiRect::all(Val::Px(5.)),
-                    ..default()
-                }),
-                // Because this is a distinct label widget and
-                // not button/list item text, this is necessary
-                // for accessibility to treat the text accordingly.
-                Label,
-            ));
-        });
+// the events are logged at the debug level, as they are sent all along the game
+fn log_ball_events(
+    mut launched: EventReader<BallLaunched>,
+    mut skill_shots: EventReader<SkillShot>,
+    mut state_changed: EventReader<BallStateChanged>,
+    mut scored: EventReader<ScoreEvent>,
+    mut saved: EventReader<BallSaved>,
+    q_balls: Query<&Ball>,
+) {
+    for event in state_changed.read() {
+        let id = q_balls.get(event.ball).map(Ball::id).ok();
+        debug!("Ball {:?} (id {id:?}) state: {:?}", event.ball, event.state);
+    }
+    for event in launched.read() {
+        debug!("Ball {:?} launched, charge: {}", event.ball, event.charge);
+    }
+    for event in skill_shots.read() {
+        debug!("Skill shot on sensor {:?}", event.sensor);
+    }
+    for event in saved.read() {
+        debug!("Ball {:?} saved", event.ball);
+    }
+    for event in scored.read() {
+        debug!("Scored {} points from {:?}", event.points, event.source);
+    }
 }
 
-#[allow(dead_code, unused_variables)]
-fn update_debug_ui(
-    mut q_txt: Query<&mut Text, With<DebugUi>>,
-    q_paddles: Query<&Transform, With<Paddle>>,
+fn log_table_events(
+    mut bumper_hits: EventReader<BumperHit>,
+    mut target_hits: EventReader<TargetHit>,
+    mut banks_complete: EventReader<BankComplete>,
+    mut switches: EventReader<SwitchEvent>,
 ) {
-    if let Ok(mut txt) = q_txt.get_single_mut() {
-        let mut line = String::new();
-        for trans in q_paddles.iter() {
-            line.push_str(format!("Transform: {trans:?} - ").as_str());
-        }
-        line.pop();
-        line.pop();
-        txt.sections[0].value = line;
+    for event in bumper_hits.read() {
+        debug!("Ball {:?} hit bumper {:?}", event.ball, event.bumper);
+    }
+    for event in target_hits.read() {
+        debug!("Target #{} of bank {:?} down", event.index, event.bank);
+    }
+    for event in banks_complete.read() {
+        debug!("Target bank {:?} complete", event.bank);
+    }
+    for event in switches.read() {
+        debug!("Switch '{}' {:?}", event.id, event.kind);
+    }
+}
+
+fn log_lane_events(
+    mut spins: EventReader<SpinnerRevolution>,
+    mut ramps: EventReader<RampEvent>,
+    mut captured: EventReader<BallCaptured>,
+    mut ejected: EventReader<BallEjected>,
+    mut grabbed: EventReader<BallGrabbed>,
+    mut released: EventReader<BallReleased>,
+) {
+    for event in spins.read() {
+        debug!("Spinner {:?} revolution", event.spinner);
+    }
+    for event in ramps.read() {
+        debug!("Ramp '{}' {:?}", event.id, event.kind);
+    }
+    for event in captured.read() {
+        debug!("Ball {:?} captured by saucer '{}'", event.ball, event.id);
+    }
+    for event in ejected.read() {
+        debug!("Ball {:?} ejected by saucer '{}'", event.ball, event.id);
+    }
+    for event in grabbed.read() {
+        debug!("Ball {:?} grabbed by magnet '{}'", event.ball, event.id);
+    }
+    for event in released.read() {
+        debug!("Ball {:?} released by magnet '{}'", event.ball, event.id);
     }
 }
 
-// load the scene from the gltf file
-fn start_assets_loading(mut commands: Commands, asset_server: Res<AssetServer>) {
-    commands.insert_resource(GameAssets {
-        scene: asset_server.load("pinball.glb#Scene0"),
-        ..default()
-    });
+fn log_mode_events(
+    mut started: EventReader<ModeStarted>,
+    mut completed: EventReader<ModeCompleted>,
+    mut failed: EventReader<ModeFailed>,
+    mut combos: EventReader<ComboEvent>,
+) {
+    for event in started.read() {
+        debug!("Mode '{}' started", event.id);
+    }
+    for event in completed.read() {
+        debug!("Mode '{}' completed", event.id);
+    }
+    for event in failed.read() {
+        debug!("Mode '{}' failed", event.id);
+    }
+    for event in combos.read() {
+        debug!(
+            "Combo '{}' x{}: {} points",
+            event.id, event.streak, event.points
+        );
+    }
 }
 
-// check if the scene is loaded and if so, get the colliders from it
-fn check_if_loaded(
-    scenes: Res<Assets<Scene>>,
-    game_assets: Res<GameAssets>,
-    mut game_state: ResMut<NextState<GameState>>,
+/// Start a multiball with 2 more balls when [M] is pressed, award an extra ball with [E]
+#[cfg(debug_assertions)]
+fn debug_awards(
+    keys: Res<Input<KeyCode>>,
+    mut multiball: EventWriter<StartMultiball>,
+    mut extra_ball: EventWriter<ExtraBall>,
 ) {
-    if scenes.get(&game_assets.scene).is_some() {
-        game_state.set(GameState::Loaded);
+    if keys.just_pressed(KeyCode::M) {
+        multiball.send(StartMultiball { balls: 2 });
+    }
+    if keys.just_pressed(KeyCode::E) {
+        extra_ball.send(ExtraBall);
     }
 }
 
@@ -0,0 +1,84 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ball::{ball_collision_started, Ball, BallState, BallStateChanged},
+    config::BoardConfig,
+    GameState,
+};
+
+pub struct DrainPlugin;
+
+impl Plugin for DrainPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, detect_drain.run_if(in_state(GameState::Loaded)));
+    }
+}
+
+/// Region of the board, below the paddles, where the balls are lost
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DrainConfig {
+    /// center of the region, on the board
+    pub pos: Vec3,
+    pub size: Vec3,
+}
+
+impl DrainConfig {
+    /// Check the size of the drain and that it reaches the board : it usually lies below the
+    /// bottom edge
+    pub fn validate(&self, board: &BoardConfig) -> Result<(), String> {
+        let size = self.size;
+        if size.x <= 0.0 || size.y <= 0.0 || size.z <= 0.0 {
+            return Err(format!("size {size} should be positive"));
+        }
+        let (hx, hz) = (board.size.x / 2.0, board.size.z / 2.0);
+        let (min_x, max_x) = (self.pos.x - size.x / 2.0, self.pos.x + size.x / 2.0);
+        let (min_z, max_z) = (self.pos.z - size.z / 2.0, self.pos.z + size.z / 2.0);
+        if min_x > hx || max_x < -hx || min_z > hz || max_z < -hz {
+            return Err(format!("drain at {} doesn't reach the board", self.pos));
+        }
+        Ok(())
+    }
+}
+
+/// Sensor of the drain
+#[derive(Component)]
+pub struct Drain;
+
+pub fn spawn_drain(builder: &mut ChildBuilder, config: &DrainConfig, board: &BoardConfig) {
+    let size = config.size;
+    builder.spawn((
+        Name::new("DRAIN"),
+        Drain,
+        TransformBundle::from_transform(Transform::from_xyz(
+            config.pos.x,
+            board.surface(),
+            config.pos.z,
+        )),
+        Collider::cuboid(size.x / 2.0, size.y / 2.0, size.z / 2.0),
+        Sensor,
+    ));
+}
+
+fn detect_drain(
+    mut collision_events: EventReader<CollisionEvent>,
+    q_drain: Query<(), With<Drain>>,
+    q_ball: Query<(), With<Ball>>,
+    mut q_balls: Query<&mut Ball>,
+    mut state_changed: EventWriter<BallStateChanged>,
+) {
+    for event in collision_events.read() {
+        let Some((ball_entity, drain)) = ball_collision_started(event, &q_ball) else {
+            continue;
+        };
+        if !q_drain.contains(drain) {
+            continue;
+        }
+        if let Ok(mut ball) = q_balls.get_mut(ball_entity) {
+            if matches!(ball.state(), BallState::InLane | BallState::InPlay) {
+                state_changed.send(ball.set_state(ball_entity, BallState::Draining));
+            }
+        }
+    }
+}
@@ -0,0 +1,262 @@
+use std::fmt;
+
+use bevy::{
+    asset::{LoadState, UntypedAssetId},
+    prelude::*,
+};
+
+use crate::GameState;
+
+pub struct ErrorPlugin;
+
+impl Plugin for ErrorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            enter_error_state.run_if(resource_added::<GameError>()),
+        )
+        .add_systems(OnEnter(GameState::Error), spawn_error_ui)
+        .add_systems(
+            Update,
+            update_error_ui
+                .run_if(in_state(GameState::Error))
+                .run_if(resource_exists_and_changed::<GameError>()),
+        )
+        .add_systems(OnExit(GameState::Error), despawn_error_ui);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Error {
+    /// The glTF file can't be loaded
+    LoadGltf(String),
+    /// A mesh of the glTF scene is not loaded
+    MissingGltfMesh {
+        node: String,
+    },
+    /// The collider of a glTF node can't be created from its mesh
+    ColliderBuildFailed {
+        node: String,
+    },
+    InvalidGltfExtras {
+        node: String,
+        reason: String,
+    },
+    /// The level file can't be read
+    LoadLevel(String),
+    /// The level file can't be deserialized
+    InvalidConfig {
+        line: usize,
+        column: usize,
+        reason: String,
+    },
+    InvalidBoard(String),
+    InvalidPaddle {
+        index: usize,
+        reason: String,
+    },
+    InvalidBumper {
+        index: usize,
+        reason: String,
+    },
+    InvalidSlingshot {
+        index: usize,
+        reason: String,
+    },
+    InvalidTargetBank {
+        index: usize,
+        reason: String,
+    },
+    InvalidRamp {
+        id: String,
+        reason: String,
+    },
+    InvalidSwitch {
+        id: String,
+        reason: String,
+    },
+    InvalidSpinner {
+        index: usize,
+        reason: String,
+    },
+    InvalidSaucer {
+        id: String,
+        reason: String,
+    },
+    InvalidMagnet {
+        id: String,
+        reason: String,
+    },
+    InvalidActuator {
+        id: String,
+        reason: String,
+    },
+    InvalidLamp {
+        id: String,
+        reason: String,
+    },
+    InvalidPlunger(String),
+    InvalidDrain(String),
+    InvalidMode {
+        id: String,
+        reason: String,
+    },
+    InvalidCombo {
+        id: String,
+        reason: String,
+    },
+    /// The rules script can't be read
+    LoadScript(String),
+    /// The rules script can't be compiled
+    InvalidScript {
+        path: String,
+        reason: String,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::LoadGltf(path) => write!(f, "can't load glTF file '{path}'"),
+            Error::MissingGltfMesh { node } => write!(f, "missing mesh for glTF node '{node}'"),
+            Error::ColliderBuildFailed { node } => {
+                write!(f, "can't build collider for glTF node '{node}'")
+            }
+            Error::InvalidGltfExtras { node, reason } => {
+                write!(f, "invalid extras for glTF node '{node}': {reason}")
+            }
+            Error::LoadLevel(path) => write!(f, "can't load level '{path}'"),
+            Error::InvalidConfig {
+                line,
+                column,
+                reason,
+            } => write!(f, "invalid level at line {line}, column {column}: {reason}"),
+            Error::InvalidBoard(reason) => write!(f, "invalid board: {reason}"),
+            Error::InvalidPaddle { index, reason } => {
+                write!(f, "invalid paddle #{index}: {reason}")
+            }
+            Error::InvalidBumper { index, reason } => {
+                write!(f, "invalid bumper #{index}: {reason}")
+            }
+            Error::InvalidSlingshot { index, reason } => {
+                write!(f, "invalid slingshot #{index}: {reason}")
+            }
+            Error::InvalidTargetBank { index, reason } => {
+                write!(f, "invalid target bank #{index}: {reason}")
+            }
+            Error::InvalidRamp { id, reason } => write!(f, "invalid ramp '{id}': {reason}"),
+            Error::InvalidSwitch { id, reason } => write!(f, "invalid switch '{id}': {reason}"),
+            Error::InvalidSpinner { index, reason } => {
+                write!(f, "invalid spinner #{index}: {reason}")
+            }
+            Error::InvalidSaucer { id, reason } => write!(f, "invalid saucer '{id}': {reason}"),
+            Error::InvalidMagnet { id, reason } => write!(f, "invalid magnet '{id}': {reason}"),
+            Error::InvalidActuator { id, reason } => {
+                write!(f, "invalid actuator '{id}': {reason}")
+            }
+            Error::InvalidLamp { id, reason } => write!(f, "invalid lamp '{id}': {reason}"),
+            Error::InvalidPlunger(reason) => write!(f, "invalid plunger: {reason}"),
+            Error::InvalidDrain(reason) => write!(f, "invalid drain: {reason}"),
+            Error::InvalidMode { id, reason } => write!(f, "invalid mode '{id}': {reason}"),
+            Error::InvalidCombo { id, reason } => write!(f, "invalid combo '{id}': {reason}"),
+            Error::LoadScript(path) => write!(f, "can't load script '{path}'"),
+            Error::InvalidScript { path, reason } => {
+                write!(f, "invalid script '{path}': {reason}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::InvalidConfig {
+            line: e.line(),
+            column: e.column(),
+            reason: e.to_string(),
+        }
+    }
+}
+
+/// Error reported to the player : inserting it switches the game to [GameState::Error].
+///
+/// It is removed when leaving this state, e.g. when the level is fixed and reloaded.
+#[derive(Resource)]
+pub struct GameError(pub Error);
+
+/// Detect the failed loads of an asset, as the asset server doesn't emit any event for them.
+///
+/// Its load state is polled, and a failure is reported once, until the asset is loaded again.
+#[derive(Default)]
+pub struct LoadFailure {
+    failed: bool,
+}
+
+impl LoadFailure {
+    /// Check if the asset has just failed to load
+    pub fn check(&mut self, asset_server: &AssetServer, id: impl Into<UntypedAssetId>) -> bool {
+        let failed = asset_server.get_load_state(id) == Some(LoadState::Failed);
+        let new_failure = failed && !self.failed;
+        self.failed = failed;
+        new_failure
+    }
+}
+
+#[derive(Component)]
+struct ErrorUi;
+
+/// Text of the error, replaced when another error happens
+#[derive(Component)]
+struct ErrorText;
+
+fn enter_error_state(error: Res<GameError>, mut game_state: ResMut<NextState<GameState>>) {
+    error!("{}", error.0);
+    game_state.set(GameState::Error);
+}
+
+fn spawn_error_ui(mut commands: Commands, error: Res<GameError>) {
+    commands
+        .spawn((
+            Name::new("ERROR"),
+            ErrorUi,
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.8).into(),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                ErrorText,
+                TextBundle::from_section(
+                    format!("Error: {}", error.0),
+                    TextStyle {
+                        font_size: 24.0,
+                        color: Color::RED,
+                        ..default()
+                    },
+                ),
+                Label,
+            ));
+        });
+}
+
+fn update_error_ui(error: Res<GameError>, mut q_text: Query<&mut Text, With<ErrorText>>) {
+    for mut text in q_text.iter_mut() {
+        text.sections[0].value = format!("Error: {}", error.0);
+    }
+}
+
+fn despawn_error_ui(mut commands: Commands, q_ui: Query<Entity, With<ErrorUi>>) {
+    for entity in q_ui.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    commands.remove_resource::<GameError>();
+}
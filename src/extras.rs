@@ -0,0 +1,159 @@
+use bevy::{ecs::system::EntityCommands, gltf::GltfExtras, prelude::*};
+use bevy_rapier3d::prelude::*;
+use serde::Deserialize;
+
+use crate::{bumper::Bumper, ramp::RampSensor, score::Scoring, switch::Switch, Error};
+
+/// Shape of the collider created for a glTF node
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ColliderShape {
+    Convex,
+    Trimesh,
+    Cuboid,
+    None,
+}
+
+/// Gameplay role of a glTF node
+#[derive(Clone, Copy, Component, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// kicks the ball like a [Bumper], with the `impulse` of the extras. As the node is only
+    /// a collider, it doesn't flash.
+    Bumper,
+}
+
+/// Extras of a glTF node, set as custom properties in Blender.
+///
+/// All the keys are optional, unknown keys are ignored :
+/// ```json
+/// {
+///     "collider": "convex" | "trimesh" | "cuboid" | "none",
+///     "restitution": 0.8,
+///     "friction": 0.2,
+///     "sensor": true,
+///     "role": "bumper",
+///     "impulse": 300,
+///     "score": 100,
+///     "switch": "lane1",
+///     "ramp": { "id": "left", "end": "entry" | "exit", "score": 500 }
+/// }
+/// ```
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct NodeExtras {
+    pub collider: Option<ColliderShape>,
+    pub restitution: Option<f32>,
+    pub friction: Option<f32>,
+    pub sensor: bool,
+    pub role: Option<Role>,
+    /// impulse given to the ball by a bumper
+    pub impulse: Option<f32>,
+    /// points given when the ball hits the node
+    pub score: Option<u32>,
+    /// id of the switch activated by the ball
+    pub switch: Option<String>,
+    /// entry or exit sensor of a ramp
+    pub ramp: Option<RampSensor>,
+}
+
+impl NodeExtras {
+    /// Parse the extras of a node, `name` is only used to report errors
+    pub fn parse(name: &str, extras: &GltfExtras) -> Result<Self, Error> {
+        serde_json::from_str(&extras.value).map_err(|e| Error::InvalidGltfExtras {
+            node: name.to_string(),
+            reason: e.to_string(),
+        })
+    }
+
+    /// Insert the physics material, sensor, role, scoring, switch and ramp components described by the extras
+    pub fn insert(&self, entity: &mut EntityCommands) {
+        if let Some(restitution) = self.restitution {
+            entity.insert(Restitution::coefficient(restitution));
+        }
+        if let Some(friction) = self.friction {
+            entity.insert(Friction::coefficient(friction));
+        }
+        if self.sensor {
+            entity.insert(Sensor);
+        }
+        if let Some(role) = self.role {
+            entity.insert(role);
+            match role {
+                Role::Bumper => {
+                    let impulse = self.impulse.unwrap_or(Bumper::DEFAULT_IMPULSE);
+                    entity.insert(Bumper::new(impulse));
+                }
+            }
+        }
+        if let Some(points) = self.score {
+            entity.insert(Scoring(points));
+        }
+        if let Some(id) = &self.switch {
+            entity.insert(Switch::new(id));
+        }
+        if let Some(ramp) = &self.ramp {
+            entity.insert(ramp.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ramp::RampEnd;
+
+    fn parse(value: &str) -> Result<NodeExtras, Error> {
+        NodeExtras::parse(
+            "Node",
+            &GltfExtras {
+                value: value.to_string(),
+            },
+        )
+    }
+
+    #[test]
+    fn parse_all_keys() {
+        let extras = parse(
+            r#"{
+                "collider": "cuboid",
+                "restitution": 0.8,
+                "friction": 0.2,
+                "sensor": true,
+                "role": "bumper",
+                "impulse": 150,
+                "score": 100,
+                "switch": "lane1",
+                "ramp": { "id": "left", "end": "exit", "score": 500 }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(extras.collider, Some(ColliderShape::Cuboid));
+        assert_eq!(extras.restitution, Some(0.8));
+        assert_eq!(extras.friction, Some(0.2));
+        assert!(extras.sensor);
+        assert_eq!(extras.role, Some(Role::Bumper));
+        assert_eq!(extras.impulse, Some(150.0));
+        assert_eq!(extras.score, Some(100));
+        assert_eq!(extras.switch.as_deref(), Some("lane1"));
+        let ramp = extras.ramp.unwrap();
+        assert_eq!(
+            (ramp.id.as_str(), ramp.end, ramp.score),
+            ("left", RampEnd::Exit, 500)
+        );
+    }
+
+    #[test]
+    fn unknown_keys_are_ignored() {
+        let extras = parse(r#"{ "blender_prop": 1, "collider": "none" }"#).unwrap();
+        assert_eq!(extras.collider, Some(ColliderShape::None));
+        assert!(!extras.sensor);
+        assert_eq!(extras.role, None);
+    }
+
+    #[test]
+    fn invalid_value_names_the_node() {
+        let error = parse(r#"{ "collider": "sphere" }"#).unwrap_err();
+        assert!(matches!(error, Error::InvalidGltfExtras { node, .. } if node == "Node"));
+    }
+}
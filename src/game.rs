@@ -0,0 +1,191 @@
+use bevy::prelude::*;
+
+use crate::{
+    ball::{Ball, BallStart, BallState, BallStateChanged, SpawnBall},
+    ball_save::{BallSave, BallSaved},
+    config::PinballConfig,
+    input::Action,
+    multiball::Multiball,
+    GameState,
+};
+
+pub struct GamePlugin;
+
+impl Plugin for GamePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Game>()
+            .add_event::<ExtraBall>()
+            .add_systems(OnEnter(GameState::Loaded), start_game)
+            .add_systems(
+                Update,
+                (award_extra_ball, next_ball).run_if(in_state(GameState::Loaded)),
+            )
+            .add_systems(OnEnter(GameState::GameOver), spawn_game_over_ui)
+            .add_systems(Update, restart.run_if(in_state(GameState::GameOver)))
+            .add_systems(OnExit(GameState::GameOver), despawn_game_over_ui);
+    }
+}
+
+/// Progress of the current game
+#[derive(Default, Resource)]
+pub struct Game {
+    /// number of the ball in play, starting at 1
+    pub ball: u32,
+    /// number of balls of the game, including the extra balls
+    pub balls: u32,
+    /// number of extra balls awarded
+    pub extra_balls: u32,
+}
+
+/// Award an extra ball, if the level allows it
+#[derive(Event)]
+pub struct ExtraBall;
+
+#[derive(Component)]
+struct GameOverUi;
+
+fn start_game(
+    mut game: ResMut<Game>,
+    pinball_config: Res<PinballConfig>,
+    mut spawn_ball: EventWriter<SpawnBall>,
+) {
+    *game = Game {
+        ball: 1,
+        balls: pinball_config.balls,
+        extra_balls: 0,
+    };
+    spawn_ball.send(SpawnBall(BallStart::Lane));
+}
+
+fn award_extra_ball(
+    mut extra_balls: EventReader<ExtraBall>,
+    mut game: ResMut<Game>,
+    pinball_config: Res<PinballConfig>,
+) {
+    for _ in extra_balls.read() {
+        if game.extra_balls < pinball_config.extra_balls {
+            game.extra_balls += 1;
+            game.balls += 1;
+            info!("Extra ball");
+        }
+    }
+}
+
+/// Return the drained balls during a ball save, else when the last ball in play is drained,
+/// give the next ball, or end the game.
+///
+/// The balls are saved if the ball save is active when they enter the drain.
+#[allow(clippy::too_many_arguments)]
+fn next_ball(
+    mut state_changed: EventReader<BallStateChanged>,
+    q_balls: Query<&Ball>,
+    mut game: ResMut<Game>,
+    mut ball_save: ResMut<BallSave>,
+    multiball: Res<Multiball>,
+    mut saved: EventWriter<BallSaved>,
+    mut spawn_ball: EventWriter<SpawnBall>,
+    mut game_state: ResMut<NextState<GameState>>,
+) {
+    let mut drained = vec![];
+    for event in state_changed.read() {
+        match event.state {
+            BallState::Draining => ball_save.latch(event.ball),
+            BallState::Drained => drained.push(event.ball),
+            _ => {}
+        }
+    }
+    if drained.is_empty() {
+        return;
+    }
+    // the balls waiting to be launched by a multiball are still in play
+    let mut balls_left = multiball.balls_left(&q_balls);
+
+    let mut lost = false;
+    for ball in drained {
+        if !ball_save.saves(ball) {
+            lost = true;
+            continue;
+        }
+        saved.send(BallSaved { ball });
+        // only one ball can wait in the plunger lane
+        let start = if balls_left {
+            BallStart::Launched
+        } else {
+            BallStart::Lane
+        };
+        balls_left = true;
+        spawn_ball.send(SpawnBall(start));
+    }
+    if !lost || balls_left {
+        return;
+    }
+
+    if game.ball < game.balls {
+        game.ball += 1;
+        info!("Ball {} / {}", game.ball, game.balls);
+        ball_save.arm();
+        spawn_ball.send(SpawnBall(BallStart::Lane));
+    } else {
+        info!("Game over");
+        game_state.set(GameState::GameOver);
+    }
+}
+
+fn spawn_game_over_ui(mut commands: Commands) {
+    commands
+        .spawn((
+            Name::new("GAME OVER"),
+            GameOverUi,
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "GAME OVER",
+                    TextStyle {
+                        font_size: 60.0,
+                        color: Color::ORANGE_RED,
+                        ..default()
+                    },
+                ),
+                Label,
+            ));
+            parent.spawn((
+                TextBundle::from_section(
+                    "Press [Enter] to play again",
+                    TextStyle {
+                        font_size: 20.0,
+                        ..default()
+                    },
+                ),
+                Label,
+            ));
+        });
+}
+
+fn despawn_game_over_ui(mut commands: Commands, q_ui: Query<Entity, With<GameOverUi>>) {
+    for entity in q_ui.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// Start a new game by going through the loading state, which respawns the board
+fn restart(
+    keys: Res<Input<KeyCode>>,
+    pinball_config: Res<PinballConfig>,
+    mut game_state: ResMut<NextState<GameState>>,
+) {
+    if pinball_config.inputs.just_pressed(Action::START, &keys) {
+        game_state.set(GameState::Loading);
+    }
+}
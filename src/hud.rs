@@ -0,0 +1,91 @@
+use bevy::prelude::*;
+
+use crate::{
+    ball_save::BallSave, combo::Combos, game::Game, multiball::Multiball, score::Score, tilt::Tilt,
+};
+
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_hud)
+            .add_systems(Update, update_hud);
+    }
+}
+
+#[derive(Component)]
+struct Hud;
+
+fn spawn_hud(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn((
+            Name::new("HUD"),
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Px(40.0),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Hud,
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                        font_size: 28.0,
+                        ..default()
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::all(Val::Px(5.)),
+                    ..default()
+                }),
+                Label,
+            ));
+        });
+}
+
+fn update_hud(
+    mut q_txt: Query<&mut Text, With<Hud>>,
+    score: Res<Score>,
+    game: Res<Game>,
+    multiball: Res<Multiball>,
+    ball_save: Res<BallSave>,
+    tilt: Res<Tilt>,
+    combos: Res<Combos>,
+) {
+    let changed = score.is_changed()
+        || game.is_changed()
+        || multiball.is_changed()
+        || ball_save.is_changed()
+        || tilt.is_changed()
+        || combos.is_changed();
+    if !changed {
+        return;
+    }
+    if let Ok(mut txt) = q_txt.get_single_mut() {
+        let mut line = format!(
+            "SCORE {}    BALL {}/{}    x{}",
+            score.points, game.ball, game.balls, score.multiplier
+        );
+        if multiball.active {
+            line.push_str("    MULTIBALL");
+        }
+        if ball_save.is_active() {
+            line.push_str("    BALL SAVE");
+        }
+        let streak = combos.streak();
+        if streak > 0 {
+            line.push_str(&format!("    COMBO x{streak}"));
+        }
+        if tilt.is_tilted() {
+            line.push_str("    TILT");
+        }
+        txt.sections[0].value = line;
+    }
+}
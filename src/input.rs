@@ -0,0 +1,62 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Names of the input actions with default keys
+pub struct Action;
+
+impl Action {
+    pub const LEFT: &'static str = "left";
+    pub const RIGHT: &'static str = "right";
+    pub const PLUNGER: &'static str = "plunger";
+    pub const START: &'static str = "start";
+    pub const NUDGE_LEFT: &'static str = "nudge_left";
+    pub const NUDGE_RIGHT: &'static str = "nudge_right";
+    pub const NUDGE_UP: &'static str = "nudge_up";
+}
+
+/// Keys bound to input actions, set in the level `inputs` :
+/// ```json
+/// "inputs": {
+///     "left": ["Left", "ShiftLeft"],
+///     "upper": ["Space"]
+/// }
+/// ```
+/// Actions not defined in the level use their default keys.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct InputConfig(HashMap<String, Vec<KeyCode>>);
+
+impl InputConfig {
+    pub fn keys(&self, action: &str) -> &[KeyCode] {
+        match self.0.get(action) {
+            Some(keys) => keys,
+            None => Self::default_keys(action),
+        }
+    }
+
+    fn default_keys(action: &str) -> &'static [KeyCode] {
+        match action {
+            Action::LEFT => &[KeyCode::Left],
+            Action::RIGHT => &[KeyCode::Right],
+            Action::PLUNGER => &[KeyCode::Space, KeyCode::Down],
+            Action::START => &[KeyCode::Return],
+            Action::NUDGE_LEFT => &[KeyCode::Z],
+            Action::NUDGE_RIGHT => &[KeyCode::Slash],
+            Action::NUDGE_UP => &[KeyCode::Up],
+            _ => &[],
+        }
+    }
+
+    pub fn pressed(&self, action: &str, keys: &Input<KeyCode>) -> bool {
+        keys.any_pressed(self.keys(action).iter().copied())
+    }
+
+    pub fn just_pressed(&self, action: &str, keys: &Input<KeyCode>) -> bool {
+        keys.any_just_pressed(self.keys(action).iter().copied())
+    }
+
+    pub fn just_released(&self, action: &str, keys: &Input<KeyCode>) -> bool {
+        keys.any_just_released(self.keys(action).iter().copied())
+    }
+}
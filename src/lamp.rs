@@ -0,0 +1,120 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{config::BoardConfig, GameState};
+
+pub struct LampPlugin;
+
+impl Plugin for LampPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SetLamp>()
+            .add_systems(Update, switch_lamps.run_if(in_state(GameState::Loaded)));
+    }
+}
+
+/// A lamp insert in the board, lit by the rules
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct LampConfig {
+    pub id: String,
+    /// center of the lamp, on the board
+    pub pos: Vec3,
+    #[serde(default = "LampConfig::default_radius")]
+    pub radius: f32,
+    /// lit when the level starts
+    #[serde(default)]
+    pub lit: bool,
+}
+
+impl LampConfig {
+    fn default_radius() -> f32 {
+        0.5
+    }
+
+    fn transform(&self, board: &BoardConfig) -> Transform {
+        Transform::from_xyz(self.pos.x, board.surface() + Lamp::HEIGHT / 2.0, self.pos.z)
+    }
+
+    /// Check the radius of the lamp and that it is on the board
+    pub fn validate(&self, board: &BoardConfig) -> Result<(), String> {
+        if self.radius <= 0.0 {
+            return Err("radius should be positive".into());
+        }
+        if !board.contains(self.pos, self.radius, self.radius) {
+            return Err(format!("lamp at {} is out of the board", self.pos));
+        }
+        Ok(())
+    }
+}
+
+/// Light or not a lamp
+#[derive(Event)]
+pub struct SetLamp {
+    pub id: String,
+    pub on: bool,
+}
+
+#[derive(Component)]
+pub struct Lamp {
+    pub id: String,
+}
+
+impl Lamp {
+    const HEIGHT: f32 = 0.02;
+    const COLOR: Color = Color::rgb(0.3, 0.2, 0.0);
+    const LIT_COLOR: Color = Color::ORANGE;
+
+    fn material(lit: bool) -> StandardMaterial {
+        if lit {
+            StandardMaterial {
+                base_color: Self::LIT_COLOR,
+                emissive: Self::LIT_COLOR,
+                ..default()
+            }
+        } else {
+            Self::COLOR.into()
+        }
+    }
+}
+
+pub fn spawn_lamp(
+    builder: &mut ChildBuilder,
+    config: &LampConfig,
+    board: &BoardConfig,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+) {
+    builder.spawn((
+        Name::new(format!("LAMP {}", config.id)),
+        Lamp {
+            id: config.id.clone(),
+        },
+        PbrBundle {
+            mesh: meshes.add(
+                shape::Cylinder {
+                    radius: config.radius,
+                    height: Lamp::HEIGHT,
+                    ..default()
+                }
+                .into(),
+            ),
+            transform: config.transform(board),
+            // each lamp has its own material, to be lit alone
+            material: materials.add(Lamp::material(config.lit)),
+            ..default()
+        },
+    ));
+}
+
+fn switch_lamps(
+    mut requests: EventReader<SetLamp>,
+    q_lamps: Query<(&Lamp, &Handle<StandardMaterial>)>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for request in requests.read() {
+        for (_, material) in q_lamps.iter().filter(|(lamp, _)| lamp.id == request.id) {
+            if let Some(material) = materials.get_mut(material) {
+                *material = Lamp::material(request.on);
+            }
+        }
+    }
+}
@@ -0,0 +1,262 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{ball::Ball, config::BoardConfig, GameState};
+
+pub struct MagnetPlugin;
+
+impl Plugin for MagnetPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SetMagnet>()
+            .add_event::<BallGrabbed>()
+            .add_event::<BallReleased>()
+            .add_systems(
+                Update,
+                (switch_magnets, attract_balls)
+                    .chain()
+                    .run_if(in_state(GameState::Loaded)),
+            );
+    }
+}
+
+/// A magnet under the board, attracting the balls in its radius when energised
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MagnetConfig {
+    pub id: String,
+    /// center of the magnet, on the board
+    pub pos: Vec3,
+    pub radius: f32,
+    /// force toward the center applied to the balls in the radius
+    pub force: f32,
+    /// energised when the level starts
+    #[serde(default)]
+    pub energised: bool,
+    /// grab and release mode : time a ball reaching the center is held, in seconds
+    #[serde(default)]
+    pub grab_time: Option<f32>,
+}
+
+impl MagnetConfig {
+    fn transform(&self, board: &BoardConfig) -> Transform {
+        Transform::from_xyz(self.pos.x, board.surface() + Ball::RADIUS, self.pos.z)
+    }
+
+    /// Check the radius of the magnet and that its center is on the board : the radius is
+    /// its reach, which can go over the edges
+    pub fn validate(&self, board: &BoardConfig) -> Result<(), String> {
+        if self.radius <= 0.0 {
+            return Err("radius should be positive".into());
+        }
+        if !board.contains(self.pos, 0.0, 0.0) {
+            return Err(format!("magnet at {} is out of the board", self.pos));
+        }
+        Ok(())
+    }
+}
+
+/// Energise or not a magnet
+#[derive(Event)]
+pub struct SetMagnet {
+    pub id: String,
+    pub on: bool,
+}
+
+/// Sent when a magnet in grab and release mode holds a ball
+#[derive(Event)]
+pub struct BallGrabbed {
+    pub id: String,
+    pub ball: Entity,
+}
+
+/// Sent when a magnet releases the ball it held
+#[derive(Event)]
+pub struct BallReleased {
+    pub id: String,
+    pub ball: Entity,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum MagnetState {
+    Off,
+    On,
+    /// holding a ball at its center
+    Holding {
+        ball: Entity,
+        timer: f32,
+    },
+    /// off for a short time, letting the released ball go
+    Releasing {
+        timer: f32,
+    },
+}
+
+#[derive(Component)]
+pub struct Magnet {
+    pub id: String,
+    radius: f32,
+    force: f32,
+    grab_time: Option<f32>,
+    state: MagnetState,
+}
+
+impl Magnet {
+    /// distance to the center under which a ball is grabbed
+    const GRAB_DISTANCE: f32 = 0.3;
+    /// time the magnet stays off after a release, in seconds
+    const RELEASE_TIME: f32 = 0.5;
+
+    fn is_on(&self) -> bool {
+        !matches!(self.state, MagnetState::Off)
+    }
+}
+
+pub fn spawn_magnet(builder: &mut ChildBuilder, config: &MagnetConfig, board: &BoardConfig) {
+    builder.spawn((
+        Name::new(format!("MAGNET {}", config.id)),
+        Magnet {
+            id: config.id.clone(),
+            radius: config.radius,
+            force: config.force,
+            grab_time: config.grab_time,
+            state: if config.energised {
+                MagnetState::On
+            } else {
+                MagnetState::Off
+            },
+        },
+        TransformBundle::from_transform(config.transform(board)),
+        // only used to show the field in the debug render, it doesn't interact with anything
+        Collider::cylinder(Ball::RADIUS, config.radius),
+        Sensor,
+        CollisionGroups::new(Group::NONE, Group::NONE),
+    ));
+}
+
+/// Let a held ball go, giving it back to the physics
+fn release_ball(
+    magnet: &Magnet,
+    ball: Entity,
+    q_bodies: &mut Query<&mut RigidBody, With<Ball>>,
+    released: &mut EventWriter<BallReleased>,
+) {
+    if let Ok(mut body) = q_bodies.get_mut(ball) {
+        *body = RigidBody::Dynamic;
+    }
+    released.send(BallReleased {
+        id: magnet.id.clone(),
+        ball,
+    });
+}
+
+fn switch_magnets(
+    mut requests: EventReader<SetMagnet>,
+    mut q_magnets: Query<&mut Magnet>,
+    mut q_bodies: Query<&mut RigidBody, With<Ball>>,
+    mut released: EventWriter<BallReleased>,
+) {
+    for request in requests.read() {
+        for mut magnet in q_magnets.iter_mut().filter(|m| m.id == request.id) {
+            if request.on == magnet.is_on() {
+                continue;
+            }
+            if let MagnetState::Holding { ball, .. } = magnet.state {
+                release_ball(&magnet, ball, &mut q_bodies, &mut released);
+            }
+            magnet.state = if request.on {
+                MagnetState::On
+            } else {
+                MagnetState::Off
+            };
+        }
+    }
+}
+
+/// Set the force applied by the magnets on each ball.
+///
+/// A grabbed ball is made kinematic and kept at the center until released.
+#[allow(clippy::type_complexity)]
+fn attract_balls(
+    mut q_magnets: Query<(&mut Magnet, &GlobalTransform)>,
+    mut q_balls: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            &mut Transform,
+            &mut Velocity,
+            &mut ExternalForce,
+        ),
+        With<Ball>,
+    >,
+    mut q_bodies: Query<&mut RigidBody, With<Ball>>,
+    time: Res<Time>,
+    mut grabbed: EventWriter<BallGrabbed>,
+    mut released: EventWriter<BallReleased>,
+) {
+    for (_, _, _, _, mut force) in q_balls.iter_mut() {
+        force.force = Vec3::ZERO;
+    }
+
+    for (mut magnet, magnet_transform) in q_magnets.iter_mut() {
+        let center = magnet_transform.translation();
+        let normal = magnet_transform.up();
+
+        match magnet.state {
+            MagnetState::Off => {}
+            MagnetState::Releasing { timer } => {
+                let timer = timer - time.delta_seconds();
+                magnet.state = if timer <= 0.0 {
+                    MagnetState::On
+                } else {
+                    MagnetState::Releasing { timer }
+                };
+            }
+            MagnetState::Holding { ball, timer } => {
+                let timer = timer - time.delta_seconds();
+                let Ok((_, _, mut transform, _, _)) = q_balls.get_mut(ball) else {
+                    magnet.state = MagnetState::On;
+                    continue;
+                };
+                transform.translation = center;
+                if timer <= 0.0 {
+                    release_ball(&magnet, ball, &mut q_bodies, &mut released);
+                    magnet.state = MagnetState::Releasing {
+                        timer: Magnet::RELEASE_TIME,
+                    };
+                } else {
+                    magnet.state = MagnetState::Holding { ball, timer };
+                }
+            }
+            MagnetState::On => {
+                for (ball, ball_transform, _, mut velocity, mut force) in q_balls.iter_mut() {
+                    let offset = center - ball_transform.translation();
+                    // distance on the board
+                    let offset = offset - normal * offset.dot(normal);
+                    let distance = offset.length();
+                    if distance > magnet.radius {
+                        continue;
+                    }
+                    if let Some(grab_time) = magnet.grab_time {
+                        // only one ball can be held
+                        if distance < Magnet::GRAB_DISTANCE && magnet.state == MagnetState::On {
+                            if let Ok(mut body) = q_bodies.get_mut(ball) {
+                                *body = RigidBody::KinematicPositionBased;
+                            }
+                            *velocity = Velocity::zero();
+                            magnet.state = MagnetState::Holding {
+                                ball,
+                                timer: grab_time,
+                            };
+                            grabbed.send(BallGrabbed {
+                                id: magnet.id.clone(),
+                                ball,
+                            });
+                            continue;
+                        }
+                    }
+                    force.force += offset.normalize_or_zero() * magnet.force;
+                }
+            }
+        }
+    }
+}
@@ -1,29 +1,54 @@
 use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
+use error::Error;
 
+mod actuator;
 mod ball;
+mod ball_save;
 mod board;
+mod bumper;
 mod camera;
+mod colliders;
+mod combo;
 mod config;
 mod debug;
+mod drain;
+mod error;
+mod extras;
+mod game;
+mod hud;
+mod input;
+mod lamp;
+mod magnet;
+mod multiball;
 mod paddle;
+mod plunger;
+mod ramp;
+mod rules;
+mod saucer;
+mod score;
+mod script;
+mod slingshot;
+mod spinner;
+mod switch;
+mod target;
+mod tilt;
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, States, Default)]
 enum GameState {
     #[default]
     Loading,
     Loaded,
+    GameOver,
+    Error,
 }
 
 #[derive(Default, Resource)]
 struct GameAssets {
     scene: Handle<Scene>,
-    colliders: Vec<(Collider, Transform)>,
-}
-
-#[derive(Debug, Clone, Copy)]
-pub enum Error {
-    LoadGltfError,
+    level: Handle<config::PinballConfig>,
+    /// colliders of the scene, once extracted
+    colliders: Option<Vec<colliders::SceneColliderDef>>,
 }
 
 fn main() {
@@ -42,12 +67,36 @@ fn main() {
         .add_plugins(RapierPhysicsPlugin::<NoUserData>::default())
         .add_state::<GameState>()
         .insert_resource(GameAssets::default())
-        .add_plugins(debug::DebugPlugin)
+        .add_plugins((debug::DebugPlugin, error::ErrorPlugin))
         .add_plugins((
+            config::ConfigPlugin,
             camera::CameraPlugin,
             board::BoardPlugin,
             ball::BallPlugin,
             paddle::PaddlePlugin,
+            plunger::PlungerPlugin,
+            drain::DrainPlugin,
+            game::GamePlugin,
+            multiball::MultiballPlugin,
+            ball_save::BallSavePlugin,
+            tilt::TiltPlugin,
+            score::ScorePlugin,
+            hud::HudPlugin,
+        ))
+        // RULES
+        .add_plugins((rules::RulesPlugin, combo::ComboPlugin, script::ScriptPlugin))
+        // TABLE ELEMENTS
+        .add_plugins((
+            bumper::BumperPlugin,
+            slingshot::SlingshotPlugin,
+            target::TargetPlugin,
+            switch::SwitchPlugin,
+            spinner::SpinnerPlugin,
+            ramp::RampPlugin,
+            saucer::SaucerPlugin,
+            magnet::MagnetPlugin,
+            actuator::ActuatorPlugin,
+            lamp::LampPlugin,
         ))
         // STARTUP
         // .add_startup_system(load_font)
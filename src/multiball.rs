@@ -0,0 +1,137 @@
+use bevy::prelude::*;
+
+use crate::{
+    ball::{Ball, BallPool, BallStart, BallState, SpawnBall},
+    score::ScoreEvent,
+    GameState,
+};
+
+pub struct MultiballPlugin;
+
+impl Plugin for MultiballPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Multiball>()
+            .add_event::<StartMultiball>()
+            .add_event::<MultiballStarted>()
+            .add_event::<MultiballEnded>()
+            .add_event::<Jackpot>()
+            .add_systems(OnEnter(GameState::Loaded), reset_multiball)
+            .add_systems(
+                Update,
+                (
+                    start_multiball,
+                    release_balls,
+                    end_multiball,
+                    award_jackpots,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Loaded)),
+            );
+    }
+}
+
+/// Add balls on the table, launched one by one from the plunger lane
+#[derive(Event)]
+pub struct StartMultiball {
+    pub balls: u32,
+}
+
+#[derive(Event)]
+pub struct MultiballStarted;
+
+/// Sent when only one ball is left on the table
+#[derive(Event)]
+pub struct MultiballEnded;
+
+/// Award a jackpot, only scored during a multiball
+#[derive(Event)]
+pub struct Jackpot {
+    pub points: u32,
+}
+
+#[derive(Default, Resource)]
+pub struct Multiball {
+    pub active: bool,
+    /// balls waiting to be launched
+    queued: u32,
+    /// time before launching the next ball, in seconds
+    timer: f32,
+}
+
+impl Multiball {
+    /// delay between two launched balls, in seconds
+    const LAUNCH_INTERVAL: f32 = 1.0;
+
+    /// Some balls are waiting to be launched, or the last one was just launched
+    pub fn is_launching(&self) -> bool {
+        self.queued > 0 || self.timer > 0.0
+    }
+
+    /// Some balls are still in play : on the table, or waiting to be launched
+    pub fn balls_left<'a>(&self, balls: impl IntoIterator<Item = &'a Ball>) -> bool {
+        self.is_launching()
+            || balls
+                .into_iter()
+                .any(|ball| ball.state() != BallState::Drained)
+    }
+}
+
+fn reset_multiball(mut multiball: ResMut<Multiball>) {
+    *multiball = Multiball::default();
+}
+
+fn start_multiball(
+    mut requests: EventReader<StartMultiball>,
+    mut multiball: ResMut<Multiball>,
+    mut started: EventWriter<MultiballStarted>,
+) {
+    for request in requests.read() {
+        multiball.queued += request.balls;
+        if !multiball.active && request.balls > 0 {
+            multiball.active = true;
+            started.send(MultiballStarted);
+        }
+    }
+}
+
+fn release_balls(
+    mut multiball: ResMut<Multiball>,
+    time: Res<Time>,
+    mut spawn_ball: EventWriter<SpawnBall>,
+) {
+    if multiball.timer > 0.0 {
+        multiball.timer = (multiball.timer - time.delta_seconds()).max(0.0);
+    }
+    if multiball.queued > 0 && multiball.timer <= 0.0 {
+        multiball.queued -= 1;
+        multiball.timer = Multiball::LAUNCH_INTERVAL;
+        spawn_ball.send(SpawnBall(BallStart::Launched));
+    }
+}
+
+fn end_multiball(
+    mut multiball: ResMut<Multiball>,
+    pool: Res<BallPool>,
+    mut ended: EventWriter<MultiballEnded>,
+) {
+    // wait for the last launched ball to be on the table
+    if multiball.active && !multiball.is_launching() && pool.len() <= 1 {
+        multiball.active = false;
+        ended.send(MultiballEnded);
+    }
+}
+
+fn award_jackpots(
+    mut jackpots: EventReader<Jackpot>,
+    multiball: Res<Multiball>,
+    mut score_events: EventWriter<ScoreEvent>,
+) {
+    for jackpot in jackpots.read() {
+        if multiball.active {
+            score_events.send(ScoreEvent {
+                points: jackpot.points,
+                source: None,
+            });
+        }
+    }
+}
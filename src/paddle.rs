@@ -2,7 +2,10 @@ use bevy::prelude::*;
 use bevy_rapier3d::prelude::*;
 use serde::{Deserialize, Serialize};
 
-use crate::config::PaddleConfig;
+use crate::{
+    config::{BoardConfig, PaddleConfig, PinballConfig},
+    tilt::Tilt,
+};
 
 pub struct PaddlePlugin;
 
@@ -20,80 +23,85 @@ pub enum PaddleType {
 
 #[derive(Clone, Component)]
 pub struct Paddle {
-    ptype: PaddleType,
+    action: String,
+    rest_angle: f32,
+    active_angle: f32,
+    stiffness: f32,
+    damping: f32,
 }
 
 impl Paddle {
-    pub const SIZE: Vec3 = Vec3::new(5.0, 0.5, 2.0);
+    pub const HEIGHT: f32 = 0.5;
     /// space between Paddle and board
     pub const SPACE: f32 = 0.5;
 
-    pub fn hx() -> f32 {
-        Self::SIZE.x / 2.0
-    }
-
     pub fn hy() -> f32 {
-        Self::SIZE.y / 2.0
-    }
-
-    pub fn hz() -> f32 {
-        Self::SIZE.z / 2.0
+        Self::HEIGHT / 2.0
     }
 
-    fn shape() -> impl Into<Mesh> {
-        shape::Box::new(Self::SIZE.x, Self::SIZE.y, Self::SIZE.z)
+    fn shape(size: Vec3) -> impl Into<Mesh> {
+        shape::Box::new(size.x, size.y, size.z)
     }
 
-    fn collider() -> Collider {
-        Collider::cuboid(Self::hx(), Self::hy(), Self::hz())
+    fn collider(size: Vec3) -> Collider {
+        Collider::cuboid(size.x / 2.0, size.y / 2.0, size.z / 2.0)
     }
 }
 
 pub fn spawn_paddle(
     builder: &mut ChildBuilder,
     config: &PaddleConfig,
+    board: &BoardConfig,
     meshes: &mut ResMut<Assets<Mesh>>,
     materials: &mut ResMut<Assets<StandardMaterial>>,
 ) {
     let board_entity = builder.parent_entity();
+    let size = config.size();
+    let (rest_angle, active_angle) = config.angles();
     builder
         .spawn((
             Name::new("PADDLE"),
             Paddle {
-                ptype: config.ptype,
+                action: config.action().to_string(),
+                rest_angle,
+                active_angle,
+                stiffness: config.settings.stiffness,
+                damping: config.settings.damping,
             },
             PbrBundle {
-                mesh: meshes.add(Paddle::shape().into()),
-                transform: config.transform(),
+                mesh: meshes.add(Paddle::shape(size).into()),
+                transform: config.transform(board),
                 material: materials.add(Color::BLUE.into()),
                 ..default()
             },
-            // Paddle::transform(&pos),
         ))
         .insert((
             RigidBody::Dynamic,
             Sleeping::disabled(),
-            Paddle::collider(),
-            ColliderMassProperties::Mass(10.0),
-            ImpulseJoint::new(board_entity, config.joint()),
+            Paddle::collider(size),
+            ColliderMassProperties::Mass(config.settings.mass),
+            ImpulseJoint::new(board_entity, config.joint(board)),
         ));
 }
 
-fn move_paddle(mut q_paddles: Query<(&Paddle, &mut ImpulseJoint)>, keys: Res<Input<KeyCode>>) {
-    let left = keys.pressed(KeyCode::Left);
-    let right = keys.pressed(KeyCode::Right);
-
-    let stiffness = 5000.0;
-    let damping = 1.0;
+fn move_paddle(
+    mut q_paddles: Query<(&Paddle, &mut ImpulseJoint)>,
+    keys: Res<Input<KeyCode>>,
+    pinball_config: Option<Res<PinballConfig>>,
+    tilt: Res<Tilt>,
+) {
+    let Some(pinball_config) = pinball_config else {
+        return;
+    };
     for (paddle, mut impulse_joint) in q_paddles.iter_mut() {
         if let Some(joint) = impulse_joint.data.as_revolute_mut() {
-            let target_pos = match paddle.ptype {
-                PaddleType::Left if left => 0.4,
-                PaddleType::Left => -0.4,
-                PaddleType::Right if right => -0.4,
-                PaddleType::Right => 0.4,
+            let active = !tilt.is_tilted() && pinball_config.inputs.pressed(&paddle.action, &keys);
+            let target_pos = if active {
+                paddle.active_angle
+            } else {
+                paddle.rest_angle
             };
-            joint.set_motor_position(target_pos, stiffness, damping);
+            joint.set_motor_position(target_pos, paddle.stiffness, paddle.damping);
         }
     }
 }
@@ -0,0 +1,247 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ball::{ball_collision_started, Ball},
+    config::{BoardConfig, PinballConfig},
+    input::Action,
+    GameState,
+};
+
+pub struct PlungerPlugin;
+
+impl Plugin for PlungerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<BallLaunched>()
+            .add_event::<SkillShot>()
+            .add_systems(
+                Update,
+                (charge_plunger, move_plunger_rod, detect_skill_shot)
+                    .run_if(in_state(GameState::Loaded)),
+            );
+    }
+}
+
+/// The plunger of the level, in its lane
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PlungerConfig {
+    /// position on the board, where the ball waits to be launched
+    pub pos: Vec3,
+    /// direction of the lane, on the board
+    pub direction: Vec3,
+    /// impulse applied to the ball when fully charged
+    pub max_impulse: f32,
+    /// time to fully charge the plunger, in seconds
+    #[serde(default = "PlungerConfig::default_charge_time")]
+    pub charge_time: f32,
+    /// launch the ball at full strength as soon as it is in the lane
+    #[serde(default)]
+    pub auto: bool,
+    /// input action, defaults to "plunger"
+    #[serde(default)]
+    pub action: Option<String>,
+}
+
+impl PlungerConfig {
+    fn default_charge_time() -> f32 {
+        1.0
+    }
+
+    pub fn action(&self) -> &str {
+        self.action.as_deref().unwrap_or(Action::PLUNGER)
+    }
+
+    /// Transform on the board, looking at the lane direction, which is not vertical as checked
+    /// by [PlungerConfig::validate]
+    fn transform(&self, board: &BoardConfig) -> Transform {
+        let pos = Vec3::new(self.pos.x, board.surface(), self.pos.z);
+        Transform::from_translation(pos).looking_to(self.direction, Vec3::Y)
+    }
+
+    /// Position of the ball waiting in the lane, relative to the board
+    pub fn ball_pos(&self, board: &BoardConfig) -> Vec3 {
+        Vec3::new(self.pos.x, board.surface() + Ball::RADIUS, self.pos.z)
+    }
+
+    /// Check the strength of the plunger, the direction of its lane and that it is on the board
+    pub fn validate(&self, board: &BoardConfig) -> Result<(), String> {
+        if self.max_impulse <= 0.0 || self.charge_time <= 0.0 {
+            return Err("max_impulse and charge_time should be positive".into());
+        }
+        // a vertical direction can't be looked to with the board normal as up
+        if Vec2::new(self.direction.x, self.direction.z).length_squared() == 0.0 {
+            return Err(format!(
+                "direction {} should be along the board",
+                self.direction
+            ));
+        }
+        if !board.contains(self.pos, 0.0, 0.0) {
+            return Err(format!("plunger at {} is out of the board", self.pos));
+        }
+        Ok(())
+    }
+}
+
+/// Sent when the plunger launches the ball
+#[derive(Event)]
+pub struct BallLaunched {
+    pub ball: Entity,
+    /// strength of the launch, between 0 and 1
+    pub charge: f32,
+}
+
+/// Sent when the ball reaches its first sensor after being launched
+#[derive(Event)]
+pub struct SkillShot {
+    pub sensor: Entity,
+}
+
+/// Sensor of the plunger lane
+#[derive(Component, Default)]
+pub struct Plunger {
+    /// between 0 and 1
+    charge: f32,
+    /// time the ball has been waiting in the lane, for the auto plunger
+    waiting: f32,
+    /// the ball has been launched and didn't reach any sensor yet
+    skill_shot_armed: bool,
+}
+
+impl Plunger {
+    /// size of the sensor, the lane being on the z axis
+    const SENSOR_SIZE: Vec3 = Vec3::new(2.5, 2.0, 4.0);
+    const ROD_SIZE: Vec3 = Vec3::new(1.0, 1.0, 1.0);
+    /// distance the rod moves back when fully charged
+    const ROD_TRAVEL: f32 = 2.0;
+    /// delay before the auto plunger launches the ball, in seconds
+    const AUTO_DELAY: f32 = 0.5;
+
+    fn rod_translation(charge: f32) -> Vec3 {
+        Vec3::new(
+            0.0,
+            Self::ROD_SIZE.y / 2.0,
+            Ball::RADIUS + Self::ROD_SIZE.z / 2.0 + charge * Self::ROD_TRAVEL,
+        )
+    }
+}
+
+/// Visual of the plunger, moving back when charged
+#[derive(Component)]
+struct PlungerRod;
+
+pub fn spawn_plunger(
+    builder: &mut ChildBuilder,
+    config: &PlungerConfig,
+    board: &BoardConfig,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+) {
+    let size = Plunger::SENSOR_SIZE;
+    builder
+        .spawn((
+            Name::new("PLUNGER"),
+            Plunger::default(),
+            SpatialBundle::from_transform(config.transform(board)),
+            Collider::cuboid(size.x / 2.0, size.y / 2.0, size.z / 2.0),
+            Sensor,
+        ))
+        .with_children(|builder| {
+            let rod = Plunger::ROD_SIZE;
+            builder.spawn((
+                Name::new("PLUNGER ROD"),
+                PlungerRod,
+                PbrBundle {
+                    mesh: meshes.add(shape::Box::new(rod.x, rod.y, rod.z).into()),
+                    transform: Transform::from_translation(Plunger::rod_translation(0.0)),
+                    material: materials.add(Color::SILVER.into()),
+                    ..default()
+                },
+            ));
+        });
+}
+
+/// Charge the plunger while its action is pressed, and launch the ball when released
+fn charge_plunger(
+    mut q_plunger: Query<(Entity, &mut Plunger)>,
+    mut q_ball: Query<(Entity, &mut ExternalImpulse), With<Ball>>,
+    rapier_context: Res<RapierContext>,
+    pinball_config: Res<PinballConfig>,
+    keys: Res<Input<KeyCode>>,
+    time: Res<Time>,
+    mut launched: EventWriter<BallLaunched>,
+) {
+    let Some(config) = &pinball_config.plunger else {
+        return;
+    };
+    let action = config.action();
+    for (plunger_entity, mut plunger) in q_plunger.iter_mut() {
+        let ball_in_lane = q_ball.iter_mut().find(|(ball, _)| {
+            rapier_context.intersection_pair(plunger_entity, *ball) == Some(true)
+        });
+        let Some((ball, mut impulse)) = ball_in_lane else {
+            plunger.charge = 0.0;
+            plunger.waiting = 0.0;
+            continue;
+        };
+
+        let charge = if config.auto {
+            plunger.waiting += time.delta_seconds();
+            (plunger.waiting >= Plunger::AUTO_DELAY).then_some(1.0)
+        } else if pinball_config.inputs.pressed(action, &keys) {
+            plunger.charge = (plunger.charge + time.delta_seconds() / config.charge_time).min(1.0);
+            None
+        } else if pinball_config.inputs.just_released(action, &keys) {
+            Some(plunger.charge).filter(|charge| *charge > 0.0)
+        } else {
+            None
+        };
+
+        if let Some(charge) = charge {
+            let direction = Quat::from_rotation_x(pinball_config.board.angle)
+                * config.direction.normalize_or_zero();
+            impulse.impulse += direction * config.max_impulse * charge;
+            plunger.charge = 0.0;
+            plunger.waiting = 0.0;
+            plunger.skill_shot_armed = true;
+            launched.send(BallLaunched { ball, charge });
+        }
+    }
+}
+
+fn move_plunger_rod(
+    q_plunger: Query<(&Plunger, &Children)>,
+    mut q_rod: Query<&mut Transform, With<PlungerRod>>,
+) {
+    for (plunger, children) in q_plunger.iter() {
+        for &child in children.iter() {
+            if let Ok(mut transform) = q_rod.get_mut(child) {
+                transform.translation = Plunger::rod_translation(plunger.charge);
+            }
+        }
+    }
+}
+
+/// Send a [SkillShot] event for the first sensor reached by the ball after its launch
+fn detect_skill_shot(
+    mut collision_events: EventReader<CollisionEvent>,
+    mut q_plunger: Query<(Entity, &mut Plunger)>,
+    q_ball: Query<(), With<Ball>>,
+    q_sensors: Query<(), With<Sensor>>,
+    mut skill_shots: EventWriter<SkillShot>,
+) {
+    for event in collision_events.read() {
+        let Some((_, sensor)) = ball_collision_started(event, &q_ball) else {
+            continue;
+        };
+        if !q_sensors.contains(sensor) {
+            continue;
+        }
+        for (plunger_entity, mut plunger) in q_plunger.iter_mut() {
+            if plunger.skill_shot_armed && sensor != plunger_entity {
+                plunger.skill_shot_armed = false;
+                skill_shots.send(SkillShot { sensor });
+            }
+        }
+    }
+}
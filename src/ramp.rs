@@ -0,0 +1,329 @@
+use std::collections::HashMap;
+
+use bevy::{
+    prelude::*,
+    render::{mesh::Indices, render_resource::PrimitiveTopology},
+};
+use bevy_rapier3d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ball::{ball_collision_started, Ball},
+    config::BoardConfig,
+    score::ScoreEvent,
+    GameState,
+};
+
+pub struct RampPlugin;
+
+impl Plugin for RampPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<RampEvent>()
+            .add_systems(Update, detect_ramps.run_if(in_state(GameState::Loaded)));
+    }
+}
+
+/// A ramp generated from a spline going through control points.
+///
+/// Ramps can also be modeled in the glTF scene : their meshes get trimesh colliders, and
+/// their entry and exit sensors are tagged with the `ramp` extras (see [crate::extras::NodeExtras]).
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RampConfig {
+    pub id: String,
+    /// control points, on the board, `y` being the height above its surface
+    pub points: Vec<Vec3>,
+    #[serde(default = "RampConfig::default_width")]
+    pub width: f32,
+    #[serde(default = "RampConfig::default_wall_height")]
+    pub wall_height: f32,
+    /// points given when the ball reaches the exit of the ramp
+    #[serde(default)]
+    pub score: u32,
+}
+
+impl RampConfig {
+    fn default_width() -> f32 {
+        3.0
+    }
+
+    fn default_wall_height() -> f32 {
+        1.0
+    }
+
+    /// Positions and directions along the spline, from the first to the last control point.
+    /// The ramp has at least 2 points, see [RampConfig::validate].
+    fn samples(&self) -> Vec<(Vec3, Vec3)> {
+        // duplicate the ends, as a Catmull-Rom spline doesn't go through its first and last points
+        let mut points = self.points.clone();
+        points.insert(0, self.points[0]);
+        points.push(self.points[self.points.len() - 1]);
+        let curve = CubicCardinalSpline::new_catmull_rom(points).to_curve();
+        let subdivisions = Ramp::SUBDIVISIONS * (self.points.len() - 1);
+        curve
+            .iter_positions(subdivisions)
+            .zip(curve.iter_velocities(subdivisions))
+            .map(|(pos, velocity)| (pos, velocity.normalize_or_zero()))
+            .collect()
+    }
+
+    /// Vertices and triangles of the floor and walls of the ramp
+    fn geometry(&self, samples: &[(Vec3, Vec3)]) -> (Vec<Vec3>, Vec<[u32; 3]>) {
+        let mut vertices = Vec::new();
+        let mut triangles = Vec::new();
+        let wall = Vec3::Y * self.wall_height;
+        for (i, &(pos, direction)) in samples.iter().enumerate() {
+            let side = direction.cross(Vec3::Y).normalize_or_zero() * self.width / 2.0;
+            // section : left wall top, left floor, right floor, right wall top
+            vertices.extend([pos - side + wall, pos - side, pos + side, pos + side + wall]);
+            if i > 0 {
+                let (prev, cur) = (4 * (i as u32 - 1), 4 * i as u32);
+                for k in 0..3 {
+                    triangles.push([prev + k, prev + k + 1, cur + k + 1]);
+                    triangles.push([prev + k, cur + k + 1, cur + k]);
+                }
+            }
+        }
+        (vertices, triangles)
+    }
+
+    /// Check the points and width of the ramp, and that it stays above the board
+    pub fn validate(&self, board: &BoardConfig) -> Result<(), String> {
+        if self.points.len() < 2 {
+            return Err("at least 2 points are needed".into());
+        }
+        if self.width <= 0.0 {
+            return Err("width should be positive".into());
+        }
+        let half_width = self.width / 2.0;
+        match self
+            .points
+            .iter()
+            .find(|point| !board.contains(**point, half_width, half_width))
+        {
+            Some(point) => Err(format!("point {point} is out of the board")),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RampEnd {
+    Entry,
+    Exit,
+}
+
+/// Sensor at an end of a ramp
+#[derive(Clone, Component, Debug, Deserialize)]
+pub struct RampSensor {
+    /// id of the ramp
+    pub id: String,
+    pub end: RampEnd,
+    /// points given when the ball exits the ramp, on the exit sensor
+    #[serde(default)]
+    pub score: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RampKind {
+    /// the ball went from the entry to the exit
+    Completed,
+    /// the ball rolled back through the entry
+    Failed,
+}
+
+#[derive(Event)]
+pub struct RampEvent {
+    pub id: String,
+    pub kind: RampKind,
+}
+
+/// Balls on a ramp, which went through its entry
+#[derive(Default)]
+struct RampBalls(Vec<Entity>);
+
+impl RampBalls {
+    /// Track a ball reaching a sensor of the ramp, returning what it made, if anything
+    fn reach(&mut self, ball: Entity, end: RampEnd) -> Option<RampKind> {
+        let was_on_ramp = self.0.contains(&ball);
+        self.0.retain(|&b| b != ball);
+        match (end, was_on_ramp) {
+            (RampEnd::Entry, false) => {
+                self.0.push(ball);
+                None
+            }
+            (RampEnd::Entry, true) => Some(RampKind::Failed),
+            (RampEnd::Exit, true) => Some(RampKind::Completed),
+            // the ball fell on the ramp, or went backward
+            (RampEnd::Exit, false) => None,
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct Ramp;
+
+impl Ramp {
+    /// number of mesh sections between two control points
+    const SUBDIVISIONS: usize = 16;
+    const COLOR: Color = Color::rgba(0.6, 0.8, 1.0, 0.6);
+    const SENSOR_HEIGHT: f32 = 2.0;
+    const SENSOR_DEPTH: f32 = 0.5;
+}
+
+pub fn spawn_ramp(
+    builder: &mut ChildBuilder,
+    config: &RampConfig,
+    board: &BoardConfig,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+) {
+    let samples = config.samples();
+    let (vertices, triangles) = config.geometry(&samples);
+
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+    mesh.insert_attribute(
+        Mesh::ATTRIBUTE_POSITION,
+        vertices.iter().map(|v| v.to_array()).collect::<Vec<_>>(),
+    );
+    mesh.set_indices(Some(Indices::U32(triangles.concat())));
+    mesh.duplicate_vertices();
+    mesh.compute_flat_normals();
+
+    builder
+        .spawn((
+            Name::new(format!("RAMP {}", config.id)),
+            Ramp,
+            PbrBundle {
+                mesh: meshes.add(mesh),
+                transform: Transform::from_xyz(0.0, board.surface(), 0.0),
+                material: materials.add(StandardMaterial {
+                    base_color: Ramp::COLOR,
+                    alpha_mode: AlphaMode::Blend,
+                    double_sided: true,
+                    cull_mode: None,
+                    ..default()
+                }),
+                ..default()
+            },
+            Collider::trimesh(vertices, triangles),
+        ))
+        .with_children(|builder| {
+            let ends = [
+                (RampEnd::Entry, samples[0]),
+                (RampEnd::Exit, samples[samples.len() - 1]),
+            ];
+            for (end, (pos, direction)) in ends {
+                let pos = pos + Vec3::Y * Ramp::SENSOR_HEIGHT / 2.0;
+                builder.spawn((
+                    Name::new(format!("RAMP {} {end:?}", config.id)),
+                    RampSensor {
+                        id: config.id.clone(),
+                        end,
+                        score: config.score,
+                    },
+                    TransformBundle::from_transform(
+                        Transform::from_translation(pos).looking_to(direction, Vec3::Y),
+                    ),
+                    Collider::cuboid(
+                        config.width / 2.0,
+                        Ramp::SENSOR_HEIGHT / 2.0,
+                        Ramp::SENSOR_DEPTH / 2.0,
+                    ),
+                    Sensor,
+                ));
+            }
+        });
+}
+
+/// Track the balls entering the ramps, to send a [RampEvent] when they reach the exit or
+/// roll back through the entry
+fn detect_ramps(
+    mut collision_events: EventReader<CollisionEvent>,
+    q_ball: Query<(), With<Ball>>,
+    q_sensors: Query<&RampSensor>,
+    // balls on each ramp
+    mut on_ramp: Local<HashMap<String, RampBalls>>,
+    mut ramp_events: EventWriter<RampEvent>,
+    mut score_events: EventWriter<ScoreEvent>,
+) {
+    for event in collision_events.read() {
+        let Some((ball, sensor_entity)) = ball_collision_started(event, &q_ball) else {
+            continue;
+        };
+        let Ok(sensor) = q_sensors.get(sensor_entity) else {
+            continue;
+        };
+
+        let balls = on_ramp.entry(sensor.id.clone()).or_default();
+        // forget the drained balls
+        balls.0.retain(|&b| q_ball.contains(b));
+        if let Some(kind) = balls.reach(ball, sensor.end) {
+            ramp_events.send(RampEvent {
+                id: sensor.id.clone(),
+                kind,
+            });
+            if kind == RampKind::Completed {
+                score_events.send(ScoreEvent {
+                    points: sensor.score,
+                    source: Some(sensor_entity),
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ball_completes_the_ramp() {
+        let mut balls = RampBalls::default();
+        let ball = Entity::from_raw(1);
+        assert_eq!(balls.reach(ball, RampEnd::Entry), None);
+        assert_eq!(balls.reach(ball, RampEnd::Exit), Some(RampKind::Completed));
+        // the ball is off the ramp
+        assert_eq!(balls.reach(ball, RampEnd::Exit), None);
+    }
+
+    #[test]
+    fn ball_rolls_back() {
+        let mut balls = RampBalls::default();
+        let ball = Entity::from_raw(1);
+        balls.reach(ball, RampEnd::Entry);
+        assert_eq!(balls.reach(ball, RampEnd::Entry), Some(RampKind::Failed));
+        // a new try
+        assert_eq!(balls.reach(ball, RampEnd::Entry), None);
+        assert_eq!(balls.reach(ball, RampEnd::Exit), Some(RampKind::Completed));
+    }
+
+    #[test]
+    fn balls_are_tracked_apart() {
+        let mut balls = RampBalls::default();
+        let (first, second) = (Entity::from_raw(1), Entity::from_raw(2));
+        balls.reach(first, RampEnd::Entry);
+        assert_eq!(balls.reach(second, RampEnd::Exit), None);
+        balls.reach(second, RampEnd::Entry);
+        assert_eq!(balls.reach(first, RampEnd::Exit), Some(RampKind::Completed));
+        assert_eq!(balls.reach(second, RampEnd::Entry), Some(RampKind::Failed));
+    }
+
+    #[test]
+    fn samples_go_through_the_ends() {
+        let config: RampConfig = serde_json::from_str(
+            r#"{ "id": "test", "points": [[0, 0, 0], [0, 1, -4], [2, 2, -8]] }"#,
+        )
+        .unwrap();
+        let samples = config.samples();
+        assert_eq!(samples.first().unwrap().0, Vec3::ZERO);
+        assert!(
+            samples
+                .last()
+                .unwrap()
+                .0
+                .distance(Vec3::new(2.0, 2.0, -8.0))
+                < 1e-4
+        );
+    }
+}
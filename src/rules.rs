@@ -0,0 +1,370 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    actuator::SetActuator,
+    ball::{BallState, BallStateChanged},
+    ball_save::StartBallSave,
+    bumper::BumperHit,
+    config::PinballConfig,
+    game::ExtraBall,
+    lamp::SetLamp,
+    magnet::SetMagnet,
+    multiball::{Jackpot, StartMultiball},
+    plunger::{BallLaunched, SkillShot},
+    ramp::{RampEvent, RampKind},
+    saucer::{BallCaptured, EjectSaucer},
+    score::{Score, ScoreEvent},
+    spinner::SpinnerRevolution,
+    switch::{SwitchEvent, SwitchKind},
+    target::{BankComplete, ResetBank, TargetBank},
+    tilt::Tilt,
+    GameState,
+};
+
+pub struct RulesPlugin;
+
+impl Plugin for RulesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Modes>()
+            .add_event::<ModeStarted>()
+            .add_event::<ModeCompleted>()
+            .add_event::<ModeFailed>()
+            .add_systems(OnEnter(GameState::Loaded), reset_modes)
+            .add_systems(
+                Update,
+                (run_modes, fail_modes)
+                    .chain()
+                    .run_if(in_state(GameState::Loaded)),
+            );
+    }
+}
+
+/// Something happening on the table, starting a mode or counting for its goals
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Trigger {
+    /// a switch is activated
+    Switch(String),
+    /// a ramp is completed
+    Ramp(String),
+    /// all the targets of a bank are down
+    Bank(String),
+    /// a ball is captured by a saucer
+    Saucer(String),
+    /// another mode is completed
+    Mode(String),
+    Bumper,
+    Spinner,
+    SkillShot,
+    /// a ball is launched by the plunger
+    Launch,
+    /// a ball drains
+    Drain,
+}
+
+/// Something done by the rules when a mode starts, is completed or fails
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleAction {
+    Score(u32),
+    /// set the factor applied to all the points scored
+    Multiplier(u32),
+    /// start a multiball with this number of added balls
+    Multiball(u32),
+    Jackpot(u32),
+    ExtraBall,
+    /// start a ball save, for a time in seconds
+    BallSave(f32),
+    Magnet {
+        id: String,
+        on: bool,
+    },
+    Actuator {
+        id: String,
+        open: bool,
+    },
+    Lamp {
+        id: String,
+        on: bool,
+    },
+    ResetBank(String),
+    EjectSaucer(String),
+}
+
+/// A goal of a mode : a trigger happening a number of times
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Goal {
+    pub trigger: Trigger,
+    #[serde(default = "Goal::default_count")]
+    pub count: u32,
+}
+
+impl Goal {
+    fn default_count() -> u32 {
+        1
+    }
+}
+
+/// A mode, or mission : once started, it is completed when all its goals are reached,
+/// or fails when its time is over
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ModeConfig {
+    pub id: String,
+    /// trigger starting the mode
+    pub start: Trigger,
+    /// time to reach the goals, in seconds. Without time, the mode runs until they are reached.
+    #[serde(default)]
+    pub time: Option<f32>,
+    pub goals: Vec<Goal>,
+    #[serde(default)]
+    pub on_start: Vec<RuleAction>,
+    /// actions done when the mode is completed
+    #[serde(default)]
+    pub rewards: Vec<RuleAction>,
+    #[serde(default)]
+    pub on_fail: Vec<RuleAction>,
+    /// the mode can be started again once completed
+    #[serde(default)]
+    pub repeat: bool,
+}
+
+impl ModeConfig {
+    /// Check the goals and time of the mode, returning the reason if invalid
+    pub fn validate(&self) -> Result<(), String> {
+        if self.goals.is_empty() {
+            return Err("at least 1 goal is needed".into());
+        }
+        if self.goals.iter().any(|goal| goal.count == 0) {
+            return Err("goal counts should be positive".into());
+        }
+        if self.time.is_some_and(|time| time <= 0.0) {
+            return Err("time should be positive".into());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Event)]
+pub struct ModeStarted {
+    pub id: String,
+}
+
+#[derive(Event)]
+pub struct ModeCompleted {
+    pub id: String,
+}
+
+#[derive(Event)]
+pub struct ModeFailed {
+    pub id: String,
+}
+
+#[derive(Clone, Debug, Default)]
+struct ModeState {
+    running: bool,
+    completed: bool,
+    /// time left, in seconds
+    timer: Option<f32>,
+    /// number of triggers counted for each goal
+    progress: Vec<u32>,
+}
+
+/// State of the modes of the level, in the order of [PinballConfig::modes]
+#[derive(Default, Resource)]
+struct Modes(Vec<ModeState>);
+
+fn reset_modes(mut modes: ResMut<Modes>, pinball_config: Res<PinballConfig>) {
+    modes.0 = vec![ModeState::default(); pinball_config.modes.len()];
+}
+
+/// The table events the rules react to
+#[derive(SystemParam)]
+pub struct RuleEvents<'w, 's> {
+    switches: EventReader<'w, 's, SwitchEvent>,
+    ramps: EventReader<'w, 's, RampEvent>,
+    banks: EventReader<'w, 's, BankComplete>,
+    captured: EventReader<'w, 's, BallCaptured>,
+    bumpers: EventReader<'w, 's, BumperHit>,
+    spins: EventReader<'w, 's, SpinnerRevolution>,
+    skill_shots: EventReader<'w, 's, SkillShot>,
+    launched: EventReader<'w, 's, BallLaunched>,
+    state_changed: EventReader<'w, 's, BallStateChanged>,
+    q_banks: Query<'w, 's, &'static TargetBank>,
+    tilt: Res<'w, Tilt>,
+}
+
+impl RuleEvents<'_, '_> {
+    /// Triggers of the frame. Nothing counts while the table is tilted.
+    pub fn triggers(&mut self) -> Vec<Trigger> {
+        let mut triggers = vec![];
+        for event in self.switches.read() {
+            if event.kind == SwitchKind::Activated {
+                triggers.push(Trigger::Switch(event.id.clone()));
+            }
+        }
+        for event in self.ramps.read() {
+            if event.kind == RampKind::Completed {
+                triggers.push(Trigger::Ramp(event.id.clone()));
+            }
+        }
+        for event in self.banks.read() {
+            if let Ok(bank) = self.q_banks.get(event.bank) {
+                triggers.push(Trigger::Bank(bank.id.clone()));
+            }
+        }
+        for event in self.captured.read() {
+            triggers.push(Trigger::Saucer(event.id.clone()));
+        }
+        triggers.extend(self.bumpers.read().map(|_| Trigger::Bumper));
+        triggers.extend(self.spins.read().map(|_| Trigger::Spinner));
+        triggers.extend(self.skill_shots.read().map(|_| Trigger::SkillShot));
+        triggers.extend(self.launched.read().map(|_| Trigger::Launch));
+        for event in self.state_changed.read() {
+            if event.state == BallState::Drained {
+                triggers.push(Trigger::Drain);
+            }
+        }
+        if self.tilt.is_tilted() {
+            triggers.clear();
+        }
+        triggers
+    }
+}
+
+/// What the rules act on
+#[derive(SystemParam)]
+pub struct RuleActions<'w, 's> {
+    score: ResMut<'w, Score>,
+    score_events: EventWriter<'w, ScoreEvent>,
+    multiball: EventWriter<'w, StartMultiball>,
+    jackpots: EventWriter<'w, Jackpot>,
+    extra_balls: EventWriter<'w, ExtraBall>,
+    ball_saves: EventWriter<'w, StartBallSave>,
+    magnets: EventWriter<'w, SetMagnet>,
+    actuators: EventWriter<'w, SetActuator>,
+    lamps: EventWriter<'w, SetLamp>,
+    bank_resets: EventWriter<'w, ResetBank>,
+    saucer_ejects: EventWriter<'w, EjectSaucer>,
+    q_banks: Query<'w, 's, (Entity, &'static TargetBank)>,
+}
+
+impl RuleActions<'_, '_> {
+    pub fn run(&mut self, actions: &[RuleAction]) {
+        for action in actions {
+            match action {
+                RuleAction::Score(points) => self.score_events.send(ScoreEvent {
+                    points: *points,
+                    source: None,
+                }),
+                RuleAction::Multiplier(multiplier) => self.score.multiplier = *multiplier,
+                RuleAction::Multiball(balls) => {
+                    self.multiball.send(StartMultiball { balls: *balls })
+                }
+                RuleAction::Jackpot(points) => self.jackpots.send(Jackpot { points: *points }),
+                RuleAction::ExtraBall => self.extra_balls.send(ExtraBall),
+                RuleAction::BallSave(time) => self.ball_saves.send(StartBallSave { time: *time }),
+                RuleAction::Magnet { id, on } => self.magnets.send(SetMagnet {
+                    id: id.clone(),
+                    on: *on,
+                }),
+                RuleAction::Actuator { id, open } => self.actuators.send(SetActuator {
+                    id: id.clone(),
+                    open: *open,
+                }),
+                RuleAction::Lamp { id, on } => self.lamps.send(SetLamp {
+                    id: id.clone(),
+                    on: *on,
+                }),
+                RuleAction::ResetBank(id) => {
+                    for (bank, _) in self.q_banks.iter().filter(|(_, b)| &b.id == id) {
+                        self.bank_resets.send(ResetBank { bank });
+                    }
+                }
+                RuleAction::EjectSaucer(id) => {
+                    self.saucer_ejects.send(EjectSaucer { id: id.clone() })
+                }
+            }
+        }
+    }
+}
+
+/// Start the modes and count their goals with the triggers of the frame.
+///
+/// A running mode only counts its goals, so the trigger starting a mode is not one of
+/// its goals. A completed mode is itself a trigger for the other modes.
+fn run_modes(
+    mut modes: ResMut<Modes>,
+    pinball_config: Res<PinballConfig>,
+    mut events: RuleEvents,
+    mut actions: RuleActions,
+    mut started: EventWriter<ModeStarted>,
+    mut completed: EventWriter<ModeCompleted>,
+) {
+    let mut triggers = events.triggers();
+    let mut index = 0;
+    while index < triggers.len() {
+        let trigger = triggers[index].clone();
+        index += 1;
+        for (config, state) in pinball_config.modes.iter().zip(modes.0.iter_mut()) {
+            if state.running {
+                let mut progressed = false;
+                for (goal, progress) in config.goals.iter().zip(state.progress.iter_mut()) {
+                    if goal.trigger == trigger && *progress < goal.count {
+                        *progress += 1;
+                        progressed = true;
+                    }
+                }
+                let done = config
+                    .goals
+                    .iter()
+                    .zip(state.progress.iter())
+                    .all(|(goal, &progress)| progress >= goal.count);
+                if progressed && done {
+                    state.running = false;
+                    state.completed = true;
+                    actions.run(&config.rewards);
+                    completed.send(ModeCompleted {
+                        id: config.id.clone(),
+                    });
+                    triggers.push(Trigger::Mode(config.id.clone()));
+                }
+            } else if config.start == trigger && (!state.completed || config.repeat) {
+                state.running = true;
+                state.timer = config.time;
+                state.progress = vec![0; config.goals.len()];
+                actions.run(&config.on_start);
+                started.send(ModeStarted {
+                    id: config.id.clone(),
+                });
+            }
+        }
+    }
+}
+
+/// Count down the time of the running modes, failing them when it is over
+fn fail_modes(
+    mut modes: ResMut<Modes>,
+    pinball_config: Res<PinballConfig>,
+    mut actions: RuleActions,
+    time: Res<Time>,
+    mut failed: EventWriter<ModeFailed>,
+) {
+    for (config, state) in pinball_config.modes.iter().zip(modes.0.iter_mut()) {
+        if !state.running {
+            continue;
+        }
+        let Some(timer) = state.timer.as_mut() else {
+            continue;
+        };
+        *timer -= time.delta_seconds();
+        if *timer <= 0.0 {
+            state.running = false;
+            state.timer = None;
+            actions.run(&config.on_fail);
+            failed.send(ModeFailed {
+                id: config.id.clone(),
+            });
+        }
+    }
+}
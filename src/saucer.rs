@@ -0,0 +1,239 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ball::Ball,
+    config::{BoardConfig, PinballConfig},
+    score::ScoreEvent,
+    GameState,
+};
+
+pub struct SaucerPlugin;
+
+impl Plugin for SaucerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<BallCaptured>()
+            .add_event::<BallEjected>()
+            .add_event::<EjectSaucer>()
+            .add_systems(
+                Update,
+                (capture_ball, eject_ball)
+                    .chain()
+                    .run_if(in_state(GameState::Loaded)),
+            );
+    }
+}
+
+/// A hole capturing the ball, then ejecting it
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SaucerConfig {
+    pub id: String,
+    /// center of the hole, on the board
+    pub pos: Vec3,
+    #[serde(default = "SaucerConfig::default_radius")]
+    pub radius: f32,
+    /// time the ball is held, in seconds. When `null`, the ball is held until an
+    /// [EjectSaucer] event, as a ball lock.
+    #[serde(default = "SaucerConfig::default_hold_time")]
+    pub hold_time: Option<f32>,
+    /// direction of the ejection, on the board
+    pub direction: Vec3,
+    pub impulse: f32,
+    /// points given when the ball is captured
+    #[serde(default)]
+    pub score: u32,
+}
+
+impl SaucerConfig {
+    fn default_radius() -> f32 {
+        1.5
+    }
+
+    fn default_hold_time() -> Option<f32> {
+        Some(1.0)
+    }
+
+    fn transform(&self, board: &BoardConfig) -> Transform {
+        Transform::from_xyz(self.pos.x, board.surface() + Ball::RADIUS, self.pos.z)
+    }
+
+    /// Check the radius of the hole and that it is on the board
+    pub fn validate(&self, board: &BoardConfig) -> Result<(), String> {
+        if self.radius <= 0.0 {
+            return Err("radius should be positive".into());
+        }
+        if !board.contains(self.pos, self.radius, self.radius) {
+            return Err(format!("saucer at {} is out of the board", self.pos));
+        }
+        Ok(())
+    }
+}
+
+/// Sent when a saucer captures a ball
+#[derive(Event)]
+pub struct BallCaptured {
+    pub id: String,
+    pub ball: Entity,
+}
+
+/// Sent when a saucer ejects its ball
+#[derive(Event)]
+pub struct BallEjected {
+    pub id: String,
+    pub ball: Entity,
+}
+
+/// Eject the ball held by a saucer
+#[derive(Event)]
+pub struct EjectSaucer {
+    pub id: String,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+enum SaucerState {
+    #[default]
+    Empty,
+    Holding {
+        ball: Entity,
+        /// time left before the ejection, if any
+        timer: Option<f32>,
+    },
+    /// the ejected ball didn't leave the saucer yet
+    Ejecting { ball: Entity },
+}
+
+#[derive(Component)]
+pub struct Saucer {
+    pub id: String,
+    hold_time: Option<f32>,
+    direction: Vec3,
+    impulse: f32,
+    score: u32,
+    state: SaucerState,
+}
+
+impl Saucer {
+    /// maximum speed of a ball to be captured
+    const SETTLE_SPEED: f32 = 8.0;
+    const COLOR: Color = Color::rgb(0.1, 0.1, 0.1);
+}
+
+pub fn spawn_saucer(
+    builder: &mut ChildBuilder,
+    config: &SaucerConfig,
+    board: &BoardConfig,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+) {
+    builder
+        .spawn((
+            Name::new(format!("SAUCER {}", config.id)),
+            Saucer {
+                id: config.id.clone(),
+                hold_time: config.hold_time,
+                direction: config.direction.normalize_or_zero(),
+                impulse: config.impulse,
+                score: config.score,
+                state: SaucerState::Empty,
+            },
+            SpatialBundle::from_transform(config.transform(board)),
+            Collider::cylinder(Ball::RADIUS, config.radius),
+            Sensor,
+        ))
+        .with_children(|builder| {
+            builder.spawn(PbrBundle {
+                mesh: meshes.add(
+                    shape::Cylinder {
+                        radius: config.radius,
+                        height: 0.02,
+                        ..default()
+                    }
+                    .into(),
+                ),
+                transform: Transform::from_xyz(0.0, 0.01 - Ball::RADIUS, 0.0),
+                material: materials.add(Saucer::COLOR.into()),
+                ..default()
+            });
+        });
+}
+
+/// Capture a ball settling in an empty saucer, and release the ejected ones once out
+fn capture_ball(
+    mut q_saucers: Query<(Entity, &mut Saucer)>,
+    mut q_balls: Query<(Entity, &mut Velocity, &mut RigidBody), With<Ball>>,
+    rapier_context: Res<RapierContext>,
+    mut captured: EventWriter<BallCaptured>,
+    mut score_events: EventWriter<ScoreEvent>,
+) {
+    for (saucer_entity, mut saucer) in q_saucers.iter_mut() {
+        match saucer.state {
+            SaucerState::Empty => {
+                let settled = q_balls.iter_mut().find(|(ball, velocity, _)| {
+                    velocity.linvel.length() < Saucer::SETTLE_SPEED
+                        && rapier_context.intersection_pair(saucer_entity, *ball) == Some(true)
+                });
+                if let Some((ball, mut velocity, mut body)) = settled {
+                    *body = RigidBody::KinematicPositionBased;
+                    *velocity = Velocity::zero();
+                    saucer.state = SaucerState::Holding {
+                        ball,
+                        timer: saucer.hold_time,
+                    };
+                    captured.send(BallCaptured {
+                        id: saucer.id.clone(),
+                        ball,
+                    });
+                    score_events.send(ScoreEvent {
+                        points: saucer.score,
+                        source: Some(saucer_entity),
+                    });
+                }
+            }
+            SaucerState::Ejecting { ball } => {
+                if rapier_context.intersection_pair(saucer_entity, ball) != Some(true) {
+                    saucer.state = SaucerState::Empty;
+                }
+            }
+            SaucerState::Holding { .. } => {}
+        }
+    }
+}
+
+/// Keep the held balls in their saucer, and eject them after their time or when asked to
+#[allow(clippy::type_complexity)]
+fn eject_ball(
+    mut q_saucers: Query<(&mut Saucer, &GlobalTransform)>,
+    mut q_balls: Query<(&mut Transform, &mut RigidBody, &mut ExternalImpulse), With<Ball>>,
+    mut requests: EventReader<EjectSaucer>,
+    pinball_config: Res<PinballConfig>,
+    time: Res<Time>,
+    mut ejected: EventWriter<BallEjected>,
+) {
+    let requested: Vec<String> = requests.read().map(|event| event.id.clone()).collect();
+    for (mut saucer, saucer_transform) in q_saucers.iter_mut() {
+        let SaucerState::Holding { ball, timer } = saucer.state else {
+            continue;
+        };
+        let Ok((mut transform, mut body, mut impulse)) = q_balls.get_mut(ball) else {
+            // the ball is gone
+            saucer.state = SaucerState::Empty;
+            continue;
+        };
+        transform.translation = saucer_transform.translation();
+
+        let timer = timer.map(|t| t - time.delta_seconds());
+        if timer.is_some_and(|t| t <= 0.0) || requested.contains(&saucer.id) {
+            let direction = Quat::from_rotation_x(pinball_config.board.angle) * saucer.direction;
+            *body = RigidBody::Dynamic;
+            impulse.impulse += direction * saucer.impulse;
+            saucer.state = SaucerState::Ejecting { ball };
+            ejected.send(BallEjected {
+                id: saucer.id.clone(),
+                ball,
+            });
+        } else {
+            saucer.state = SaucerState::Holding { ball, timer };
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::{
+    ball::{ball_collision_started, Ball},
+    tilt::Tilt,
+    GameState,
+};
+
+pub struct ScorePlugin;
+
+impl Plugin for ScorePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Score>()
+            .add_event::<ScoreEvent>()
+            .add_systems(OnEnter(GameState::Loaded), reset_score)
+            .add_systems(
+                Update,
+                (score_collisions, add_score)
+                    .chain()
+                    .run_if(in_state(GameState::Loaded)),
+            );
+    }
+}
+
+/// Score of the current game
+#[derive(Resource)]
+pub struct Score {
+    pub points: u64,
+    /// factor applied to all the points scored
+    pub multiplier: u32,
+}
+
+impl Default for Score {
+    fn default() -> Self {
+        Score {
+            points: 0,
+            multiplier: 1,
+        }
+    }
+}
+
+/// Points given when the ball hits this element
+#[derive(Clone, Copy, Component, Debug)]
+pub struct Scoring(pub u32);
+
+/// Points scored, before the multiplier
+#[derive(Event)]
+pub struct ScoreEvent {
+    pub points: u32,
+    /// the element which gave the points, if any
+    pub source: Option<Entity>,
+}
+
+fn reset_score(mut score: ResMut<Score>) {
+    *score = Score::default();
+}
+
+fn score_collisions(
+    mut collision_events: EventReader<CollisionEvent>,
+    q_ball: Query<(), With<Ball>>,
+    q_scoring: Query<&Scoring>,
+    mut score_events: EventWriter<ScoreEvent>,
+) {
+    for event in collision_events.read() {
+        let Some((_, element)) = ball_collision_started(event, &q_ball) else {
+            continue;
+        };
+        if let Ok(scoring) = q_scoring.get(element) {
+            score_events.send(ScoreEvent {
+                points: scoring.0,
+                source: Some(element),
+            });
+        }
+    }
+}
+
+/// Add the scored points, unless the table is tilted
+fn add_score(mut score_events: EventReader<ScoreEvent>, mut score: ResMut<Score>, tilt: Res<Tilt>) {
+    for event in score_events.read() {
+        if tilt.is_tilted() {
+            continue;
+        }
+        score.points += u64::from(event.points) * u64::from(score.multiplier);
+    }
+}
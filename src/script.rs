@@ -0,0 +1,483 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use bevy::{
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    prelude::*,
+    utils::BoxedFuture,
+};
+use rhai::{
+    module_resolvers::DummyModuleResolver, CallFnOptions, Dynamic, Engine, FuncArgs, Map, Scope,
+    AST, INT,
+};
+
+use crate::{
+    combo::ComboEvent,
+    config::PinballConfig,
+    error::{GameError, LoadFailure},
+    rules::{ModeCompleted, ModeFailed, ModeStarted, RuleAction, RuleActions, RuleEvents, Trigger},
+    tilt::Tilt,
+    Error, GameState,
+};
+
+pub struct ScriptPlugin;
+
+impl Plugin for ScriptPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<TableScript>()
+            .register_asset_loader(TableScriptLoader)
+            .init_resource::<ScriptRuntime>()
+            .add_systems(OnEnter(GameState::Loaded), reset_script)
+            .add_systems(
+                Update,
+                (
+                    load_script.run_if(resource_exists_and_changed::<PinballConfig>()),
+                    compile_script,
+                    check_script_failed.run_if(in_state(GameState::Loaded)),
+                ),
+            )
+            .add_systems(Update, run_script.run_if(in_state(GameState::Loaded)));
+    }
+}
+
+/// Source of a rules script, written in [Rhai](https://rhai.rs).
+///
+/// The script defines the functions called on the table events, all optional :
+/// - `on_start()` : the level starts
+/// - `on_switch(id)`, `on_ramp(id)`, `on_bank(id)`, `on_saucer(id)`
+/// - `on_bumper()`, `on_spinner()`, `on_skill_shot()`, `on_launch()`, `on_drain()`
+/// - `on_paddle(action)` : a paddle is flipped
+/// - `on_combo(id, streak)` : a combo is made
+/// - `on_mode(id, event)` : a mode is "started", "completed" or "failed"
+/// - `on_timer(name)` : a timer started by the script is over
+///
+/// Its state is kept in `this`, an object map. It acts on the table with the functions
+/// registered by [ScriptRuntime::engine].
+#[derive(Asset, TypePath)]
+pub struct TableScript {
+    source: String,
+}
+
+struct TableScriptLoader;
+
+impl AssetLoader for TableScriptLoader {
+    type Asset = TableScript;
+    type Settings = ();
+    type Error = Error;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<TableScript, Error>> {
+        Box::pin(async move {
+            let mut source = String::new();
+            match reader.read_to_string(&mut source).await {
+                Ok(_) => Ok(TableScript { source }),
+                Err(_) => Err(Error::LoadScript(load_context.path().display().to_string())),
+            }
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["rhai"]
+    }
+}
+
+/// What a script asks for, applied once its function returns
+enum ScriptCommand {
+    Action(RuleAction),
+    StartTimer { name: String, time: f32 },
+    CancelTimer(String),
+}
+
+#[derive(Clone, Default)]
+struct ScriptCommands(Arc<Mutex<Vec<ScriptCommand>>>);
+
+impl ScriptCommands {
+    fn push(&self, command: ScriptCommand) {
+        if let Ok(mut commands) = self.0.lock() {
+            commands.push(command);
+        }
+    }
+
+    fn take(&self) -> Vec<ScriptCommand> {
+        self.0
+            .lock()
+            .map(|mut commands| std::mem::take(&mut *commands))
+            .unwrap_or_default()
+    }
+}
+
+/// Script of the level, and its state
+#[derive(Resource)]
+struct ScriptRuntime {
+    engine: Engine,
+    commands: ScriptCommands,
+    handle: Option<Handle<TableScript>>,
+    ast: Option<AST>,
+    /// the `this` object of the script functions
+    state: Dynamic,
+    /// time left of the timers started by the script, in seconds
+    timers: HashMap<String, f32>,
+    /// `on_start` is called as soon as the script is compiled
+    starting: bool,
+}
+
+impl Default for ScriptRuntime {
+    fn default() -> Self {
+        let commands = ScriptCommands::default();
+        ScriptRuntime {
+            engine: Self::engine(&commands),
+            commands,
+            handle: None,
+            ast: None,
+            state: Dynamic::from_map(Map::new()),
+            timers: HashMap::new(),
+            starting: false,
+        }
+    }
+}
+
+impl ScriptRuntime {
+    /// operations allowed for each function call, to stop infinite loops
+    const MAX_OPERATIONS: u64 = 100_000;
+    const MAX_CALL_LEVELS: usize = 32;
+    /// maximum depths of the expressions, at global level and in the functions
+    const MAX_EXPR_DEPTHS: (usize, usize) = (64, 32);
+    /// maximum sizes of the strings, arrays and maps, to bound the memory of a script
+    const MAX_STRING_SIZE: usize = 1024;
+    const MAX_ARRAY_SIZE: usize = 1024;
+    const MAX_MAP_SIZE: usize = 1024;
+
+    /// Sandboxed engine : the scripts can't import other files or evaluate code, their
+    /// resources are limited, and they only act on the table through the registered functions
+    fn engine(commands: &ScriptCommands) -> Engine {
+        let mut engine = Engine::new();
+        engine
+            .set_module_resolver(DummyModuleResolver::new())
+            .disable_symbol("eval")
+            .set_max_operations(Self::MAX_OPERATIONS)
+            .set_max_call_levels(Self::MAX_CALL_LEVELS)
+            .set_max_expr_depths(Self::MAX_EXPR_DEPTHS.0, Self::MAX_EXPR_DEPTHS.1)
+            .set_max_string_size(Self::MAX_STRING_SIZE)
+            .set_max_array_size(Self::MAX_ARRAY_SIZE)
+            .set_max_map_size(Self::MAX_MAP_SIZE)
+            .on_print(|text| info!("Script: {text}"))
+            .on_debug(|text, _, pos| info!("Script debug {pos}: {text}"));
+
+        let action = |commands: &ScriptCommands| {
+            let commands = commands.clone();
+            move |action: RuleAction| commands.push(ScriptCommand::Action(action))
+        };
+        let push = action(commands);
+        engine.register_fn("score", move |points: INT| {
+            push(RuleAction::Score(to_u32(points)))
+        });
+        let push = action(commands);
+        engine.register_fn("multiplier", move |multiplier: INT| {
+            push(RuleAction::Multiplier(to_u32(multiplier)))
+        });
+        let push = action(commands);
+        engine.register_fn("multiball", move |balls: INT| {
+            push(RuleAction::Multiball(to_u32(balls)))
+        });
+        let push = action(commands);
+        engine.register_fn("jackpot", move |points: INT| {
+            push(RuleAction::Jackpot(to_u32(points)))
+        });
+        let push = action(commands);
+        engine.register_fn("extra_ball", move || push(RuleAction::ExtraBall));
+        let push = action(commands);
+        engine.register_fn("ball_save", move |time: f64| {
+            push(RuleAction::BallSave(time as f32))
+        });
+        let push = action(commands);
+        engine.register_fn("lamp", move |id: &str, on: bool| {
+            push(RuleAction::Lamp { id: id.into(), on })
+        });
+        let push = action(commands);
+        engine.register_fn("magnet", move |id: &str, on: bool| {
+            push(RuleAction::Magnet { id: id.into(), on })
+        });
+        let push = action(commands);
+        engine.register_fn("actuator", move |id: &str, open: bool| {
+            push(RuleAction::Actuator {
+                id: id.into(),
+                open,
+            })
+        });
+        let push = action(commands);
+        engine.register_fn("reset_bank", move |id: &str| {
+            push(RuleAction::ResetBank(id.into()))
+        });
+        let push = action(commands);
+        engine.register_fn("eject_saucer", move |id: &str| {
+            push(RuleAction::EjectSaucer(id.into()))
+        });
+
+        let timers = commands.clone();
+        engine.register_fn("start_timer", move |name: &str, time: f64| {
+            timers.push(ScriptCommand::StartTimer {
+                name: name.into(),
+                time: time as f32,
+            })
+        });
+        let timers = commands.clone();
+        engine.register_fn("cancel_timer", move |name: &str| {
+            timers.push(ScriptCommand::CancelTimer(name.into()))
+        });
+        engine
+    }
+
+    /// Call a function of the script, if it defines it
+    fn call(&mut self, name: &str, args: impl FuncArgs) {
+        let Some(ast) = &self.ast else {
+            return;
+        };
+        if !ast.iter_functions().any(|f| f.name == name) {
+            return;
+        }
+        let options = CallFnOptions::new()
+            .eval_ast(false)
+            .bind_this_ptr(&mut self.state);
+        let result = self.engine.call_fn_with_options::<Dynamic>(
+            options,
+            &mut Scope::new(),
+            ast,
+            name,
+            args,
+        );
+        if let Err(e) = result {
+            error!("Script function '{name}' failed: {e}");
+        }
+    }
+
+    fn call_trigger(&mut self, trigger: Trigger) {
+        match trigger {
+            Trigger::Switch(id) => self.call("on_switch", (id,)),
+            Trigger::Ramp(id) => self.call("on_ramp", (id,)),
+            Trigger::Bank(id) => self.call("on_bank", (id,)),
+            Trigger::Saucer(id) => self.call("on_saucer", (id,)),
+            // sent by the modes, seen by the script with `on_mode`
+            Trigger::Mode(_) => {}
+            Trigger::Bumper => self.call("on_bumper", ()),
+            Trigger::Spinner => self.call("on_spinner", ()),
+            Trigger::SkillShot => self.call("on_skill_shot", ()),
+            Trigger::Launch => self.call("on_launch", ()),
+            Trigger::Drain => self.call("on_drain", ()),
+        }
+    }
+}
+
+fn to_u32(value: INT) -> u32 {
+    value.clamp(0, INT::from(u32::MAX)) as u32
+}
+
+fn load_script(
+    asset_server: Res<AssetServer>,
+    pinball_config: Res<PinballConfig>,
+    mut runtime: ResMut<ScriptRuntime>,
+) {
+    let handle = pinball_config
+        .script
+        .as_ref()
+        .map(|path| asset_server.load(path.clone()));
+    if handle != runtime.handle {
+        runtime.handle = handle;
+        runtime.ast = None;
+    }
+}
+
+// compile the script when loaded, or when the file is modified. The game goes on with the
+// new script and the same state, or restarts if the previous script was invalid.
+fn compile_script(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<TableScript>>,
+    scripts: Res<Assets<TableScript>>,
+    mut runtime: ResMut<ScriptRuntime>,
+    pinball_config: Option<Res<PinballConfig>>,
+    game_error: Option<Res<GameError>>,
+    mut game_state: ResMut<NextState<GameState>>,
+) {
+    let Some(handle) = runtime.handle.clone() else {
+        return;
+    };
+    let changed = events
+        .read()
+        .any(|e| e.is_modified(&handle) || e.is_added(&handle));
+    let Some(script) = scripts.get(&handle).filter(|_| changed) else {
+        return;
+    };
+    match runtime.engine.compile(&script.source) {
+        Ok(ast) => {
+            info!("Script compiled");
+            runtime.ast = Some(ast);
+            let script_error = game_error
+                .is_some_and(|e| matches!(e.0, Error::LoadScript(_) | Error::InvalidScript { .. }));
+            if script_error {
+                game_state.set(GameState::Loading);
+            }
+        }
+        Err(e) => {
+            let path = pinball_config
+                .and_then(|config| config.script.clone())
+                .unwrap_or_default();
+            commands.insert_resource(GameError(Error::InvalidScript {
+                path,
+                reason: e.to_string(),
+            }));
+        }
+    }
+}
+
+// check if the script file can't be loaded
+fn check_script_failed(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    runtime: Res<ScriptRuntime>,
+    pinball_config: Res<PinballConfig>,
+    mut load_failure: Local<LoadFailure>,
+) {
+    let Some(handle) = &runtime.handle else {
+        return;
+    };
+    if load_failure.check(&asset_server, handle) {
+        let path = pinball_config.script.clone().unwrap_or_default();
+        commands.insert_resource(GameError(Error::LoadScript(path)));
+    }
+}
+
+fn reset_script(mut runtime: ResMut<ScriptRuntime>) {
+    runtime.state = Dynamic::from_map(Map::new());
+    runtime.timers.clear();
+    runtime.commands.take();
+    runtime.starting = true;
+}
+
+/// Call the functions of the script for the events of the frame, then apply what it asked for
+#[allow(clippy::too_many_arguments)]
+fn run_script(
+    mut runtime: ResMut<ScriptRuntime>,
+    mut events: RuleEvents,
+    mut mode_started: EventReader<ModeStarted>,
+    mut mode_completed: EventReader<ModeCompleted>,
+    mut mode_failed: EventReader<ModeFailed>,
+    mut combos: EventReader<ComboEvent>,
+    mut actions: RuleActions,
+    pinball_config: Res<PinballConfig>,
+    keys: Res<Input<KeyCode>>,
+    tilt: Res<Tilt>,
+    time: Res<Time>,
+) {
+    if runtime.starting && runtime.ast.is_some() {
+        runtime.starting = false;
+        runtime.call("on_start", ());
+    }
+
+    for trigger in events.triggers() {
+        runtime.call_trigger(trigger);
+    }
+    let inputs = &pinball_config.inputs;
+    let mut flipped: Vec<&str> = vec![];
+    for paddle in pinball_config.paddles.iter() {
+        let action = paddle.action();
+        // the paddles are disabled while tilted
+        if !tilt.is_tilted() && inputs.just_pressed(action, &keys) && !flipped.contains(&action) {
+            flipped.push(action);
+            runtime.call("on_paddle", (action.to_string(),));
+        }
+    }
+    for event in mode_started.read() {
+        runtime.call("on_mode", (event.id.clone(), "started".to_string()));
+    }
+    for event in mode_completed.read() {
+        runtime.call("on_mode", (event.id.clone(), "completed".to_string()));
+    }
+    for event in mode_failed.read() {
+        runtime.call("on_mode", (event.id.clone(), "failed".to_string()));
+    }
+
+    for event in combos.read() {
+        runtime.call("on_combo", (event.id.clone(), INT::from(event.streak)));
+    }
+
+    let mut expired: Vec<String> = vec![];
+    runtime.timers.retain(|name, timer| {
+        *timer -= time.delta_seconds();
+        if *timer <= 0.0 {
+            expired.push(name.clone());
+        }
+        *timer > 0.0
+    });
+    expired.sort();
+    for name in expired {
+        runtime.call("on_timer", (name,));
+    }
+
+    for command in runtime.commands.take() {
+        match command {
+            ScriptCommand::Action(action) => actions.run(&[action]),
+            ScriptCommand::StartTimer { name, time } => {
+                runtime.timers.insert(name, time);
+            }
+            ScriptCommand::CancelTimer(name) => {
+                runtime.timers.remove(&name);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn eval_is_disabled() {
+        let engine = ScriptRuntime::engine(&ScriptCommands::default());
+        assert!(engine
+            .compile(r#"fn on_start() { eval("score(10)"); }"#)
+            .is_err());
+    }
+
+    #[test]
+    fn imports_are_not_resolved() {
+        let engine = ScriptRuntime::engine(&ScriptCommands::default());
+        assert!(engine.run(r#"import "other" as other;"#).is_err());
+    }
+
+    #[test]
+    fn infinite_loops_are_stopped() {
+        let engine = ScriptRuntime::engine(&ScriptCommands::default());
+        assert!(engine.run("loop {}").is_err());
+    }
+
+    #[test]
+    fn functions_push_commands() {
+        let commands = ScriptCommands::default();
+        let engine = ScriptRuntime::engine(&commands);
+        engine
+            .run(r#"score(100); lamp("lane", true); start_timer("hurry_up", 2.0);"#)
+            .unwrap();
+        let commands = commands.take();
+        assert!(matches!(
+            commands[..],
+            [
+                ScriptCommand::Action(RuleAction::Score(100)),
+                ScriptCommand::Action(RuleAction::Lamp { .. }),
+                ScriptCommand::StartTimer { .. },
+            ]
+        ));
+    }
+
+    #[test]
+    fn level_script_compiles() {
+        let engine = ScriptRuntime::engine(&ScriptCommands::default());
+        engine
+            .compile(include_str!("../assets/level.rhai"))
+            .unwrap();
+    }
+}
@@ -0,0 +1,233 @@
+use bevy::{prelude::*, render::render_resource::PrimitiveTopology};
+use bevy_rapier3d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ball::{ball_collision_started, Ball},
+    config::BoardConfig,
+    paddle::PaddleType,
+    score::ScoreEvent,
+    GameState,
+};
+
+pub struct SlingshotPlugin;
+
+impl Plugin for SlingshotPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, kick_ball.run_if(in_state(GameState::Loaded)));
+    }
+}
+
+/// A triangular slingshot, above a paddle.
+///
+/// `pos` is the bottom outer corner of the triangle. A left slingshot goes right for `width`
+/// and up for `length`, its kicking face being the side between these two corners. A right
+/// slingshot is mirrored.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SlingshotConfig {
+    /// position of the bottom outer corner, on the board
+    pub pos: Vec3,
+    pub ptype: PaddleType,
+    #[serde(default = "SlingshotConfig::default_width")]
+    pub width: f32,
+    #[serde(default = "SlingshotConfig::default_length")]
+    pub length: f32,
+    /// impulse applied to the ball when it hits the kicking face
+    pub impulse: f32,
+    /// minimum speed of the ball to fire the slingshot
+    #[serde(default = "SlingshotConfig::default_threshold")]
+    pub threshold: f32,
+    /// points given when the slingshot fires
+    #[serde(default)]
+    pub score: u32,
+}
+
+impl SlingshotConfig {
+    fn default_width() -> f32 {
+        3.0
+    }
+
+    fn default_length() -> f32 {
+        6.0
+    }
+
+    fn default_threshold() -> f32 {
+        2.0
+    }
+
+    fn sign(&self) -> f32 {
+        match self.ptype {
+            PaddleType::Left => 1.0,
+            PaddleType::Right => -1.0,
+        }
+    }
+
+    /// Corners of the triangle, relative to `pos` : outer bottom, inner bottom and top
+    fn corners(&self) -> [Vec3; 3] {
+        [
+            Vec3::ZERO,
+            Vec3::new(self.sign() * self.width, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, -self.length),
+        ]
+    }
+
+    /// Normal of the kicking face, relative to the slingshot
+    fn kick_direction(&self) -> Vec3 {
+        Vec3::new(self.sign() * self.length, 0.0, -self.width).normalize()
+    }
+
+    /// Range of the slingshot on the x axis
+    fn x_range(&self) -> (f32, f32) {
+        let x = self.pos.x + self.sign() * self.width;
+        (self.pos.x.min(x), self.pos.x.max(x))
+    }
+
+    fn transform(&self, board: &BoardConfig) -> Transform {
+        Transform::from_xyz(self.pos.x, board.surface(), self.pos.z)
+    }
+
+    /// Check the size of the slingshot, needed to build its collider, and that it is on the
+    /// board
+    pub fn validate(&self, board: &BoardConfig) -> Result<(), String> {
+        if self.width <= 0.0 || self.length <= 0.0 {
+            return Err("width and length should be positive".into());
+        }
+        let (min_x, max_x) = self.x_range();
+        let center = Vec3::new((min_x + max_x) / 2.0, 0.0, self.pos.z - self.length / 2.0);
+        if !board.contains(center, self.width / 2.0, self.length / 2.0) {
+            return Err(format!("slingshot at {} is out of the board", self.pos));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Component)]
+pub struct Slingshot {
+    impulse: f32,
+    threshold: f32,
+    score: u32,
+    /// corner of the kicking face, relative to the slingshot
+    face: Vec3,
+    /// normal of the kicking face, relative to the slingshot
+    direction: Vec3,
+}
+
+impl Slingshot {
+    const HEIGHT: f32 = 1.5;
+    const COLOR: Color = Color::ORANGE;
+
+    /// Points of the triangular prism
+    fn points(corners: &[Vec3; 3]) -> Vec<Vec3> {
+        let up = Vec3::Y * Self::HEIGHT;
+        corners
+            .iter()
+            .flat_map(|&corner| [corner, corner + up])
+            .collect()
+    }
+
+    /// Flat shaded mesh of the triangular prism, with the faces oriented outward
+    fn shape(corners: &[Vec3; 3]) -> Mesh {
+        let points = Self::points(corners);
+        let center = points.iter().sum::<Vec3>() / points.len() as f32;
+        // points are ordered (bottom, top) for each corner
+        let (b0, t0, b1, t1, b2, t2) = (0, 1, 2, 3, 4, 5);
+        let triangles = [
+            [b0, b1, b2],
+            [t0, t1, t2],
+            [b0, b1, t1],
+            [b0, t1, t0],
+            [b1, b2, t2],
+            [b1, t2, t1],
+            [b2, b0, t0],
+            [b2, t0, t2],
+        ];
+        let mut positions = Vec::new();
+        for [a, b, c] in triangles {
+            let (a, mut b, mut c) = (points[a], points[b], points[c]);
+            let normal = (b - a).cross(c - a);
+            if normal.dot((a + b + c) / 3.0 - center) < 0.0 {
+                std::mem::swap(&mut b, &mut c);
+            }
+            positions.extend([a.to_array(), b.to_array(), c.to_array()]);
+        }
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        mesh.compute_flat_normals();
+        mesh
+    }
+
+    fn collider(corners: &[Vec3; 3]) -> Collider {
+        // not flat, as the width and length are checked by [SlingshotConfig::validate]
+        Collider::convex_hull(&Self::points(corners)).expect("a triangular prism is convex")
+    }
+}
+
+pub fn spawn_slingshot(
+    builder: &mut ChildBuilder,
+    config: &SlingshotConfig,
+    board: &BoardConfig,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+) {
+    let corners = config.corners();
+    builder.spawn((
+        Name::new("SLINGSHOT"),
+        Slingshot {
+            impulse: config.impulse,
+            threshold: config.threshold,
+            score: config.score,
+            face: corners[2],
+            direction: config.kick_direction(),
+        },
+        PbrBundle {
+            mesh: meshes.add(Slingshot::shape(&corners)),
+            transform: config.transform(board),
+            material: materials.add(Slingshot::COLOR.into()),
+            ..default()
+        },
+        Slingshot::collider(&corners),
+    ));
+}
+
+/// Fire the ball away from the kicking face of a slingshot, if it hits it fast enough
+fn kick_ball(
+    mut collision_events: EventReader<CollisionEvent>,
+    q_slingshots: Query<(&Slingshot, &GlobalTransform)>,
+    q_ball: Query<(), With<Ball>>,
+    mut q_balls: Query<(&GlobalTransform, &Velocity, &mut ExternalImpulse), With<Ball>>,
+    mut score_events: EventWriter<ScoreEvent>,
+) {
+    for event in collision_events.read() {
+        let Some((ball_entity, slingshot_entity)) = ball_collision_started(event, &q_ball) else {
+            continue;
+        };
+        let Ok((ball_transform, velocity, mut impulse)) = q_balls.get_mut(ball_entity) else {
+            continue;
+        };
+        let Ok((slingshot, slingshot_transform)) = q_slingshots.get(slingshot_entity) else {
+            continue;
+        };
+
+        // only the kicking face fires, the ball being on its outer side
+        let ball_pos = slingshot_transform
+            .affine()
+            .inverse()
+            .transform_point3(ball_transform.translation());
+        if (ball_pos - slingshot.face).dot(slingshot.direction) <= 0.0 {
+            continue;
+        }
+        // the contact already changed the velocity along the face normal, use the whole speed
+        if velocity.linvel.length() < slingshot.threshold {
+            continue;
+        }
+        let direction = slingshot_transform
+            .compute_transform()
+            .rotation
+            .mul_vec3(slingshot.direction);
+        impulse.impulse += direction * slingshot.impulse;
+        score_events.send(ScoreEvent {
+            points: slingshot.score,
+            source: Some(slingshot_entity),
+        });
+    }
+}
@@ -0,0 +1,169 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{config::BoardConfig, score::ScoreEvent, GameState};
+
+pub struct SpinnerPlugin;
+
+impl Plugin for SpinnerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SpinnerRevolution>().add_systems(
+            Update,
+            count_revolutions.run_if(in_state(GameState::Loaded)),
+        );
+    }
+}
+
+/// A flap spinning freely around a horizontal axis, across a lane
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SpinnerConfig {
+    /// center of the lane, on the board
+    pub pos: Vec3,
+    /// rotation of the spinner around the board normal, its axis being along x at 0
+    #[serde(default)]
+    pub angle: f32,
+    /// width of the flap, across the lane
+    #[serde(default = "SpinnerConfig::default_width")]
+    pub width: f32,
+    /// angular damping of the flap, slowing it down
+    #[serde(default = "SpinnerConfig::default_damping")]
+    pub damping: f32,
+    /// points given for each revolution
+    #[serde(default)]
+    pub score: u32,
+}
+
+impl SpinnerConfig {
+    fn default_width() -> f32 {
+        3.0
+    }
+
+    fn default_damping() -> f32 {
+        0.5
+    }
+
+    fn transform(&self, board: &BoardConfig) -> Transform {
+        Transform::from_xyz(
+            self.pos.x,
+            board.surface() + Spinner::AXIS_HEIGHT - Spinner::FLAP_HEIGHT / 2.0,
+            self.pos.z,
+        )
+        .with_rotation(Quat::from_rotation_y(self.angle))
+    }
+
+    /// Revolute joint between the board and the top of the flap
+    fn joint(&self, board: &BoardConfig) -> impl Into<GenericJoint> {
+        let axis = Quat::from_rotation_y(self.angle) * Vec3::X;
+        let parent_pos = Vec3::new(
+            self.pos.x,
+            board.surface() + Spinner::AXIS_HEIGHT,
+            self.pos.z,
+        );
+        let flap_pos = Vec3::new(0.0, Spinner::FLAP_HEIGHT / 2.0, 0.0);
+        let mut joint: GenericJoint = RevoluteJointBuilder::new(axis)
+            .local_anchor1(parent_pos)
+            .local_anchor2(flap_pos)
+            .into();
+        // the flap is already rotated by the angle
+        joint.set_local_axis2(Vec3::X);
+        joint
+    }
+
+    /// Check the flap of the spinner and that it is across a lane of the board
+    pub fn validate(&self, board: &BoardConfig) -> Result<(), String> {
+        if self.width <= 0.0 {
+            return Err("width should be positive".into());
+        }
+        if self.damping < 0.0 {
+            return Err("damping should not be negative".into());
+        }
+        let end = Quat::from_rotation_y(self.angle) * Vec3::X * self.width / 2.0;
+        if !board.contains(self.pos + end, 0.0, 0.0) || !board.contains(self.pos - end, 0.0, 0.0) {
+            return Err(format!("spinner at {} is out of the board", self.pos));
+        }
+        Ok(())
+    }
+}
+
+/// Sent for each full revolution of a spinner
+#[derive(Event)]
+pub struct SpinnerRevolution {
+    pub spinner: Entity,
+}
+
+#[derive(Component)]
+pub struct Spinner {
+    score: u32,
+    /// angle turned since the last revolution, in radians
+    angle: f32,
+}
+
+impl Spinner {
+    /// height of the axis above the board surface
+    const AXIS_HEIGHT: f32 = 2.5;
+    const FLAP_HEIGHT: f32 = 2.0;
+    const FLAP_THICKNESS: f32 = 0.1;
+    const MASS: f32 = 1.0;
+    const COLOR: Color = Color::SILVER;
+}
+
+pub fn spawn_spinner(
+    builder: &mut ChildBuilder,
+    config: &SpinnerConfig,
+    board: &BoardConfig,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+) {
+    let board_entity = builder.parent_entity();
+    let size = Vec3::new(config.width, Spinner::FLAP_HEIGHT, Spinner::FLAP_THICKNESS);
+    builder
+        .spawn((
+            Name::new("SPINNER"),
+            Spinner {
+                score: config.score,
+                angle: 0.0,
+            },
+            PbrBundle {
+                mesh: meshes.add(shape::Box::new(size.x, size.y, size.z).into()),
+                transform: config.transform(board),
+                material: materials.add(Spinner::COLOR.into()),
+                ..default()
+            },
+        ))
+        .insert((
+            RigidBody::Dynamic,
+            Sleeping::disabled(),
+            Collider::cuboid(size.x / 2.0, size.y / 2.0, size.z / 2.0),
+            ColliderMassProperties::Mass(Spinner::MASS),
+            Damping {
+                linear_damping: 0.0,
+                angular_damping: config.damping,
+            },
+            Velocity::default(),
+            ImpulseJoint::new(board_entity, config.joint(board)),
+        ));
+}
+
+/// Integrate the rotation of the spinners, sending an event for each revolution
+fn count_revolutions(
+    mut q_spinners: Query<(Entity, &mut Spinner, &Velocity, &GlobalTransform)>,
+    time: Res<Time>,
+    mut revolutions: EventWriter<SpinnerRevolution>,
+    mut score_events: EventWriter<ScoreEvent>,
+) {
+    for (entity, mut spinner, velocity, transform) in q_spinners.iter_mut() {
+        let axis = transform.compute_transform().rotation * Vec3::X;
+        spinner.angle += velocity.angvel.dot(axis) * time.delta_seconds();
+        while spinner.angle.abs() >= TAU {
+            spinner.angle -= TAU.copysign(spinner.angle);
+            revolutions.send(SpinnerRevolution { spinner: entity });
+            score_events.send(ScoreEvent {
+                points: spinner.score,
+                source: Some(entity),
+            });
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ball::{ball_and_other, Ball},
+    config::BoardConfig,
+    score::Scoring,
+    GameState,
+};
+
+pub struct SwitchPlugin;
+
+impl Plugin for SwitchPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SwitchEvent>()
+            .add_systems(Update, detect_switches.run_if(in_state(GameState::Loaded)));
+    }
+}
+
+/// A switch of the level : a rollover lane sensor, or a solid stand-up target
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SwitchConfig {
+    pub id: String,
+    /// center of the switch, on the board
+    pub pos: Vec3,
+    pub size: Vec3,
+    /// a stand-up target the ball bounces on, instead of a sensor the ball goes through
+    #[serde(default)]
+    pub solid: bool,
+    /// points given when activated
+    #[serde(default)]
+    pub score: u32,
+}
+
+impl SwitchConfig {
+    fn transform(&self, board: &BoardConfig) -> Transform {
+        Transform::from_xyz(self.pos.x, board.surface() + self.size.y / 2.0, self.pos.z)
+    }
+
+    /// Check the size of the switch and that it is on the board
+    pub fn validate(&self, board: &BoardConfig) -> Result<(), String> {
+        let size = self.size;
+        if size.x <= 0.0 || size.y <= 0.0 || size.z <= 0.0 {
+            return Err(format!("size {size} should be positive"));
+        }
+        if !board.contains(self.pos, size.x / 2.0, size.z / 2.0) {
+            return Err(format!("switch at {} is out of the board", self.pos));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SwitchKind {
+    /// a ball entered the switch
+    Activated,
+    /// the last ball left the switch
+    Released,
+}
+
+#[derive(Event)]
+pub struct SwitchEvent {
+    pub id: String,
+    pub kind: SwitchKind,
+}
+
+/// A switch, activated while a ball touches it
+#[derive(Component)]
+pub struct Switch {
+    pub id: String,
+    /// number of balls touching the switch
+    balls: usize,
+}
+
+impl Switch {
+    const COLOR: Color = Color::CYAN;
+
+    pub fn new(id: impl Into<String>) -> Self {
+        Switch {
+            id: id.into(),
+            balls: 0,
+        }
+    }
+}
+
+pub fn spawn_switch(
+    builder: &mut ChildBuilder,
+    config: &SwitchConfig,
+    board: &BoardConfig,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+) {
+    let size = config.size;
+    let transform = config.transform(board);
+    let mut entity = builder.spawn((
+        Name::new(format!("SWITCH {}", config.id)),
+        Switch::new(&config.id),
+        Collider::cuboid(size.x / 2.0, size.y / 2.0, size.z / 2.0),
+    ));
+    if config.solid {
+        entity.insert(PbrBundle {
+            mesh: meshes.add(shape::Box::new(size.x, size.y, size.z).into()),
+            transform,
+            material: materials.add(Switch::COLOR.into()),
+            ..default()
+        });
+    } else {
+        entity.insert((TransformBundle::from_transform(transform), Sensor));
+    }
+    if config.score > 0 {
+        entity.insert(Scoring(config.score));
+    }
+}
+
+/// Send the [SwitchEvent]s when the balls start or stop touching the switches
+fn detect_switches(
+    mut collision_events: EventReader<CollisionEvent>,
+    q_ball: Query<(), With<Ball>>,
+    mut q_switches: Query<&mut Switch>,
+    mut switch_events: EventWriter<SwitchEvent>,
+) {
+    for event in collision_events.read() {
+        let (e1, e2, started) = match *event {
+            CollisionEvent::Started(e1, e2, _) => (e1, e2, true),
+            CollisionEvent::Stopped(e1, e2, _) => (e1, e2, false),
+        };
+        let switch_entity = match ball_and_other(e1, e2, &q_ball) {
+            Some((_, other)) => other,
+            // the ball may already be despawned when the contact stops
+            None if !started => match (q_switches.contains(e1), q_switches.contains(e2)) {
+                (true, false) => e1,
+                (false, true) => e2,
+                _ => continue,
+            },
+            None => continue,
+        };
+        let Ok(mut switch) = q_switches.get_mut(switch_entity) else {
+            continue;
+        };
+        let kind = if started {
+            switch.balls += 1;
+            (switch.balls == 1).then_some(SwitchKind::Activated)
+        } else {
+            switch.balls = switch.balls.saturating_sub(1);
+            (switch.balls == 0).then_some(SwitchKind::Released)
+        };
+        if let Some(kind) = kind {
+            switch_events.send(SwitchEvent {
+                id: switch.id.clone(),
+                kind,
+            });
+        }
+    }
+}
@@ -0,0 +1,270 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ball::{ball_collision_started, Ball},
+    config::BoardConfig,
+    score::ScoreEvent,
+    GameState,
+};
+
+pub struct TargetPlugin;
+
+impl Plugin for TargetPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<TargetHit>()
+            .add_event::<BankComplete>()
+            .add_event::<ResetBank>()
+            .add_systems(
+                Update,
+                (hit_targets, reset_banks, move_targets)
+                    .chain()
+                    .run_if(in_state(GameState::Loaded)),
+            );
+    }
+}
+
+/// A bank of drop targets, side by side
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TargetBankConfig {
+    /// id of the bank, for the rules
+    #[serde(default)]
+    pub id: String,
+    /// center of the bank, on the board
+    pub pos: Vec3,
+    /// rotation of the bank around the board normal, its targets facing down the board at 0
+    #[serde(default)]
+    pub angle: f32,
+    /// number of targets
+    pub count: usize,
+    /// distance between the centers of two targets
+    #[serde(default = "TargetBankConfig::default_spacing")]
+    pub spacing: f32,
+    /// delay before raising the targets once they are all down, in seconds.
+    /// Without delay, the bank waits for a [ResetBank] event.
+    #[serde(default)]
+    pub reset_delay: Option<f32>,
+    /// points given for each target dropped
+    #[serde(default)]
+    pub score: u32,
+    /// points given when the bank is complete
+    #[serde(default)]
+    pub bonus: u32,
+}
+
+impl TargetBankConfig {
+    fn default_spacing() -> f32 {
+        2.0
+    }
+
+    fn transform(&self, board: &BoardConfig) -> Transform {
+        Transform::from_xyz(self.pos.x, board.surface(), self.pos.z)
+            .with_rotation(Quat::from_rotation_y(self.angle))
+    }
+
+    /// Distance from the center of the bank to the outer side of its last target.
+    /// The bank has at least one target, checked first by [TargetBankConfig::validate].
+    fn half_width(&self) -> f32 {
+        self.target_x(self.count - 1) + DropTarget::SIZE.x / 2.0
+    }
+
+    /// Position of a target, relative to the bank
+    fn target_x(&self, index: usize) -> f32 {
+        (index as f32 - (self.count - 1) as f32 / 2.0) * self.spacing
+    }
+
+    /// Check the targets of the bank and that all of them are on the board
+    pub fn validate(&self, board: &BoardConfig) -> Result<(), String> {
+        if self.count == 0 || self.spacing <= 0.0 {
+            return Err("count and spacing should be positive".into());
+        }
+        // both ends of the bank, rotated with it
+        let end = Quat::from_rotation_y(self.angle) * Vec3::X * self.half_width();
+        if !board.contains(self.pos + end, 0.0, 0.0) || !board.contains(self.pos - end, 0.0, 0.0) {
+            return Err(format!("target bank at {} is out of the board", self.pos));
+        }
+        Ok(())
+    }
+}
+
+/// Sent when a drop target is hit and goes down
+#[derive(Event)]
+pub struct TargetHit {
+    pub bank: Entity,
+    /// index of the target in its bank
+    pub index: usize,
+}
+
+/// Sent when all the targets of a bank are down
+#[derive(Event)]
+pub struct BankComplete {
+    pub bank: Entity,
+}
+
+/// Raise all the targets of a bank
+#[derive(Event)]
+pub struct ResetBank {
+    pub bank: Entity,
+}
+
+#[derive(Component)]
+pub struct TargetBank {
+    pub id: String,
+    reset_delay: Option<f32>,
+    score: u32,
+    bonus: u32,
+    /// time left before raising the targets, when complete
+    reset_timer: Option<f32>,
+}
+
+#[derive(Component)]
+pub struct DropTarget {
+    index: usize,
+    down: bool,
+}
+
+impl DropTarget {
+    const SIZE: Vec3 = Vec3::new(1.6, 1.5, 0.3);
+    const COLOR: Color = Color::GREEN;
+    /// speed of the target when going up or down
+    const SPEED: f32 = 10.0;
+
+    /// Height of the target center, relative to the board surface
+    fn height(down: bool) -> f32 {
+        if down {
+            -Self::SIZE.y
+        } else {
+            Self::SIZE.y / 2.0
+        }
+    }
+}
+
+pub fn spawn_target_bank(
+    builder: &mut ChildBuilder,
+    config: &TargetBankConfig,
+    board: &BoardConfig,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<StandardMaterial>>,
+) {
+    let size = DropTarget::SIZE;
+    let mesh = meshes.add(shape::Box::new(size.x, size.y, size.z).into());
+    let material = materials.add(DropTarget::COLOR.into());
+    builder
+        .spawn((
+            Name::new("TARGET BANK"),
+            TargetBank {
+                id: config.id.clone(),
+                reset_delay: config.reset_delay,
+                score: config.score,
+                bonus: config.bonus,
+                reset_timer: None,
+            },
+            SpatialBundle::from_transform(config.transform(board)),
+        ))
+        .with_children(|builder| {
+            for index in 0..config.count {
+                let pos = Vec3::new(config.target_x(index), DropTarget::height(false), 0.0);
+                builder.spawn((
+                    Name::new("DROP TARGET"),
+                    DropTarget { index, down: false },
+                    PbrBundle {
+                        mesh: mesh.clone(),
+                        transform: Transform::from_translation(pos),
+                        material: material.clone(),
+                        ..default()
+                    },
+                    RigidBody::KinematicPositionBased,
+                    Collider::cuboid(size.x / 2.0, size.y / 2.0, size.z / 2.0),
+                ));
+            }
+        });
+}
+
+/// Drop the targets hit by a ball, and check if their bank is complete
+fn hit_targets(
+    mut collision_events: EventReader<CollisionEvent>,
+    q_ball: Query<(), With<Ball>>,
+    mut q_targets: Query<(&mut DropTarget, &Parent)>,
+    mut q_banks: Query<(&mut TargetBank, &Children)>,
+    mut hits: EventWriter<TargetHit>,
+    mut completes: EventWriter<BankComplete>,
+    mut score_events: EventWriter<ScoreEvent>,
+) {
+    for event in collision_events.read() {
+        let Some((_, target_entity)) = ball_collision_started(event, &q_ball) else {
+            continue;
+        };
+        let Ok((mut target, parent)) = q_targets.get_mut(target_entity) else {
+            continue;
+        };
+        if target.down {
+            continue;
+        }
+        target.down = true;
+        let index = target.index;
+        let bank_entity = parent.get();
+        let Ok((mut bank, children)) = q_banks.get_mut(bank_entity) else {
+            continue;
+        };
+        hits.send(TargetHit {
+            bank: bank_entity,
+            index,
+        });
+        score_events.send(ScoreEvent {
+            points: bank.score,
+            source: Some(target_entity),
+        });
+
+        let complete = children
+            .iter()
+            .all(|&child| q_targets.get(child).map_or(true, |(t, _)| t.down));
+        if complete {
+            bank.reset_timer = bank.reset_delay;
+            completes.send(BankComplete { bank: bank_entity });
+            score_events.send(ScoreEvent {
+                points: bank.bonus,
+                source: Some(bank_entity),
+            });
+        }
+    }
+}
+
+/// Raise the targets of the banks after their delay, or when asked to
+fn reset_banks(
+    mut resets: EventReader<ResetBank>,
+    mut q_banks: Query<(Entity, &mut TargetBank, &Children)>,
+    mut q_targets: Query<&mut DropTarget>,
+    time: Res<Time>,
+) {
+    let requested: Vec<Entity> = resets.read().map(|event| event.bank).collect();
+    for (entity, mut bank, children) in q_banks.iter_mut() {
+        let delay_elapsed = match bank.reset_timer.as_mut() {
+            Some(timer) => {
+                *timer -= time.delta_seconds();
+                *timer <= 0.0
+            }
+            None => false,
+        };
+        if !delay_elapsed && !requested.contains(&entity) {
+            continue;
+        }
+        bank.reset_timer = None;
+        for &child in children.iter() {
+            if let Ok(mut target) = q_targets.get_mut(child) {
+                target.down = false;
+            }
+        }
+    }
+}
+
+fn move_targets(mut q_targets: Query<(&DropTarget, &mut Transform)>, time: Res<Time>) {
+    let step = DropTarget::SPEED * time.delta_seconds();
+    for (target, mut transform) in q_targets.iter_mut() {
+        let height = DropTarget::height(target.down);
+        let y = transform.translation.y;
+        if y != height {
+            transform.translation.y = y + (height - y).clamp(-step, step);
+        }
+    }
+}
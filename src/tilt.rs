@@ -0,0 +1,146 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ball::{Ball, BallState, BallStateChanged},
+    ball_save::BallSave,
+    config::PinballConfig,
+    input::Action,
+    multiball::Multiball,
+    GameState,
+};
+
+pub struct TiltPlugin;
+
+impl Plugin for TiltPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Tilt>()
+            .add_event::<TiltWarning>()
+            .add_event::<Tilted>()
+            .add_systems(OnEnter(GameState::Loaded), reset_tilt)
+            .add_systems(
+                Update,
+                (nudge, end_tilt).run_if(in_state(GameState::Loaded)),
+            );
+    }
+}
+
+/// Nudge and tilt settings of the level
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct TiltConfig {
+    /// impulse applied to the balls by a nudge
+    pub impulse: f32,
+    /// increase of the tilt meter for each nudge
+    pub increment: f32,
+    /// decrease of the tilt meter per second
+    pub decay: f32,
+    /// level of the tilt meter giving a warning
+    pub warning: f32,
+    /// level of the tilt meter tilting the table
+    pub threshold: f32,
+}
+
+impl Default for TiltConfig {
+    fn default() -> Self {
+        TiltConfig {
+            impulse: 100.0,
+            increment: 1.0,
+            decay: 0.5,
+            warning: 2.0,
+            threshold: 3.0,
+        }
+    }
+}
+
+/// Sent when a nudge brings the tilt meter above the warning level
+#[derive(Event)]
+pub struct TiltWarning;
+
+/// Sent when the table is tilted
+#[derive(Event)]
+pub struct Tilted;
+
+/// While tilted, the paddles are disabled and nothing scores, until the ball drains
+#[derive(Default, Resource)]
+pub struct Tilt {
+    meter: f32,
+    tilted: bool,
+}
+
+impl Tilt {
+    pub fn is_tilted(&self) -> bool {
+        self.tilted
+    }
+}
+
+fn reset_tilt(mut tilt: ResMut<Tilt>) {
+    *tilt = Tilt::default();
+}
+
+/// Push the balls in the direction of the nudge, and fill the tilt meter
+#[allow(clippy::too_many_arguments)]
+fn nudge(
+    mut tilt: ResMut<Tilt>,
+    mut q_balls: Query<&mut ExternalImpulse, With<Ball>>,
+    mut ball_save: ResMut<BallSave>,
+    pinball_config: Res<PinballConfig>,
+    keys: Res<Input<KeyCode>>,
+    time: Res<Time>,
+    mut warnings: EventWriter<TiltWarning>,
+    mut tilted: EventWriter<Tilted>,
+) {
+    let config = &pinball_config.tilt;
+    if tilt.meter > 0.0 {
+        tilt.meter = (tilt.meter - config.decay * time.delta_seconds()).max(0.0);
+    }
+
+    let inputs = &pinball_config.inputs;
+    let direction = [
+        (Action::NUDGE_LEFT, Vec3::NEG_X),
+        (Action::NUDGE_RIGHT, Vec3::X),
+        (Action::NUDGE_UP, Vec3::NEG_Z),
+    ]
+    .into_iter()
+    .filter(|(action, _)| inputs.just_pressed(action, &keys))
+    .map(|(_, direction)| direction)
+    .sum::<Vec3>();
+    if direction == Vec3::ZERO || tilt.tilted {
+        return;
+    }
+
+    let direction = Quat::from_rotation_x(pinball_config.board.angle) * direction.normalize();
+    for mut impulse in q_balls.iter_mut() {
+        impulse.impulse += direction * config.impulse;
+    }
+
+    tilt.meter += config.increment;
+    if tilt.meter >= config.threshold {
+        info!("Tilt");
+        tilt.tilted = true;
+        ball_save.cancel();
+        tilted.send(Tilted);
+    } else if tilt.meter >= config.warning {
+        info!("Tilt warning");
+        warnings.send(TiltWarning);
+    }
+}
+
+/// The tilt ends when the last ball in play drains
+fn end_tilt(
+    mut tilt: ResMut<Tilt>,
+    mut state_changed: EventReader<BallStateChanged>,
+    q_balls: Query<&Ball>,
+    multiball: Res<Multiball>,
+) {
+    let drained = state_changed
+        .read()
+        .any(|event| event.state == BallState::Drained);
+    if !drained || !tilt.tilted {
+        return;
+    }
+    if !multiball.balls_left(&q_balls) {
+        *tilt = Tilt::default();
+    }
+}